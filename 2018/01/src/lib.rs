@@ -0,0 +1,504 @@
+//! Day 1: Chronal Calibration.
+//!
+//! ```
+//! use advent_2018_01::Day01;
+//! use advent_common::Solution;
+//!
+//! let changes = Day01::parse("+3\n+3\n+4\n-2\n-4").unwrap();
+//! assert_eq!(Day01::part1(&changes).to_string(), "4");
+//! assert_eq!(Day01::part2(&changes).to_string(), "10");
+//! ```
+//!
+//! Beyond the two answers, [`Trajectory`] summarizes where the frequency goes, reading the changes
+//! as a stream if need be, and [`revisited`] lists the frequencies a few passes come back to.
+//! Frequencies are kept as `i128`, so even a very long list of `i64` changes can't overflow.
+
+use advent_common::{error, input, Answer, ParseError, Rng, Solution};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead};
+
+pub struct Day01;
+
+/// Where a frequency is reached for the second time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat {
+    pub frequency: i128,
+    /// How many passes through the list came before the one it's in.
+    pub pass: u128,
+    /// Position in the list of the change that reaches it.
+    pub index: usize,
+}
+
+/// The first frequency reached twice when `changes` are applied over and over from 0, or `None`
+/// if no frequency ever is.
+///
+/// Each pass visits the frequencies of the first pass shifted by the drift, the sum of all the
+/// changes. So a frequency can only come back after whole passes, to one that was the same modulo
+/// the drift, and the repeat is found without stepping through the passes in between.
+pub fn first_repeat(changes: &[i64]) -> Option<Repeat> {
+    let n = changes.len();
+    let (before, drift) = first_pass(changes);
+    // step `pass * n + i` reaches `before[i] + pass * drift`, with step 0 being the start
+    let repeat = |pass: u128, i: usize, frequency: i128| {
+        Some(match i {
+            0 => Repeat {
+                frequency,
+                pass: pass - 1,
+                index: n - 1,
+            },
+            _ => Repeat {
+                frequency,
+                pass,
+                index: i - 1,
+            },
+        })
+    };
+
+    let mut seen = HashMap::new();
+    for (i, &f) in before.iter().enumerate() {
+        if seen.insert(f, i).is_some() {
+            return repeat(0, i, f);
+        }
+    }
+    if n == 0 {
+        return None;
+    }
+    if drift == 0 {
+        return repeat(1, 0, 0);
+    }
+
+    // frequencies with the same remainder, in the order the drift carries them
+    let mut groups: HashMap<i128, Vec<(i128, usize)>> = HashMap::new();
+    for (i, &f) in before.iter().enumerate() {
+        groups.entry(f.rem_euclid(drift)).or_default().push((f, i));
+    }
+    let mut first: Option<(u128, usize, i128)> = None;
+    for group in groups.values_mut() {
+        group.sort_unstable_by_key(|&(f, _)| f * drift.signum());
+        for pair in group.windows(2) {
+            let ((from, i), (to, _)) = (pair[0], pair[1]);
+            // the earliest any frequency comes back is when it reaches the next one along
+            let passes = ((to - from) / drift) as u128;
+            if first.is_none_or(|(p, j, _)| (passes, i) < (p, j)) {
+                first = Some((passes, i, to));
+            }
+        }
+    }
+    let (pass, i, frequency) = first?;
+    repeat(pass, i, frequency)
+}
+
+/// The frequency before each change of the first pass, and the drift of a whole pass.
+fn first_pass(changes: &[i64]) -> (Vec<i128>, i128) {
+    let mut before = Vec::with_capacity(changes.len());
+    let mut drift = 0;
+    for &change in changes {
+        before.push(drift);
+        drift += i128::from(change);
+    }
+    (before, drift)
+}
+
+/// Parse a single change like `+7` or `-3`.
+pub fn change(line: &str) -> Result<i64, ParseError> {
+    error::field(line, "a frequency change like +7 or -3")
+}
+
+/// Where one pass through the changes takes the frequency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Trajectory {
+    /// How many changes there are in a pass.
+    pub changes: u64,
+    /// The frequency at the end of the first pass, which every later pass shifts by again.
+    pub drift: i128,
+    /// The lowest frequency of the first pass, counting the start.
+    pub lowest: i128,
+    /// The highest frequency of the first pass, counting the start.
+    pub highest: i128,
+}
+
+impl Trajectory {
+    pub fn push(&mut self, change: i64) {
+        self.changes += 1;
+        self.drift += i128::from(change);
+        self.lowest = self.lowest.min(self.drift);
+        self.highest = self.highest.max(self.drift);
+    }
+
+    pub fn of(changes: &[i64]) -> Trajectory {
+        let mut trajectory = Trajectory::default();
+        for &change in changes {
+            trajectory.push(change);
+        }
+        trajectory
+    }
+
+    /// Follow the changes as they're read, a line at a time, so however many there are they never
+    /// all have to be in memory. Bad lines are reported as [`io::ErrorKind::InvalidData`] errors
+    /// wrapping a [`ParseError`].
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Trajectory> {
+        let mut trajectory = Trajectory::default();
+        // blank lines are only allowed at the end, like `input::normalize` allows
+        let mut blank = None;
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_start_matches('\u{feff}').trim_end_matches('\r');
+            if line.trim().is_empty() {
+                blank = blank.or(Some(idx + 1));
+                continue;
+            }
+            let invalid = |e: ParseError| io::Error::new(io::ErrorKind::InvalidData, e);
+            if let Some(at) = blank {
+                return Err(invalid(change("").unwrap_err().at_line(at)));
+            }
+            trajectory.push(change(line).map_err(|e| invalid(e.at_line(idx + 1)))?);
+        }
+        Ok(trajectory)
+    }
+
+    /// The lowest and highest frequencies over the first `cycles` passes.
+    pub fn range(&self, cycles: u64) -> (i128, i128) {
+        let shift = i128::from(cycles.saturating_sub(1)) * self.drift;
+        (self.lowest + shift.min(0), self.highest + shift.max(0))
+    }
+}
+
+/// Every frequency reached more than once in the first `cycles` passes through `changes`, counting
+/// the start, with how many times it was, lowest first.
+pub fn revisited(changes: &[i64], cycles: u64) -> Vec<(i128, u64)> {
+    let (before, drift) = first_pass(changes);
+    if before.is_empty() || cycles == 0 {
+        return Vec::new();
+    }
+    let mut visits: BTreeMap<i128, u64> = BTreeMap::new();
+    if drift == 0 {
+        for &f in &before {
+            *visits.entry(f).or_default() += cycles;
+        }
+        // the last pass ends back at the start
+        *visits.entry(0).or_default() += 1;
+    } else {
+        // each frequency of the first pass starts a run, `drift` apart, that can only overlap
+        // the runs of frequencies with the same remainder. In units of the drift from that
+        // remainder, runs are intervals to sweep through.
+        let unit = drift.abs();
+        let mut runs: HashMap<i128, Vec<(i128, i64)>> = HashMap::new();
+        for (i, &f) in before.iter().enumerate() {
+            let at = f.div_euclid(unit);
+            // the start's run goes one further, to where the last pass ends
+            let length = i128::from(cycles) + i128::from(i == 0);
+            let (start, end) = if drift > 0 {
+                (at, at + length)
+            } else {
+                (at - length + 1, at + 1)
+            };
+            let events = runs.entry(f.rem_euclid(unit)).or_default();
+            events.push((start, 1));
+            events.push((end, -1));
+        }
+        for (remainder, mut events) in runs {
+            events.sort_unstable();
+            let mut overlapping = 0;
+            for (idx, &(at, delta)) in events.iter().enumerate() {
+                overlapping += delta;
+                let next = match events.get(idx + 1) {
+                    Some(&(next, _)) if next > at => next,
+                    _ => continue,
+                };
+                if overlapping > 1 {
+                    for unit_at in at..next {
+                        *visits.entry(remainder + unit_at * unit).or_default() +=
+                            overlapping as u64;
+                    }
+                }
+            }
+        }
+    }
+    visits.into_iter().filter(|&(_, n)| n > 1).collect()
+}
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 1;
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        input::parse_lines(input, change)
+    }
+
+    fn part1(vals: &Vec<i64>) -> Answer {
+        Trajectory::of(vals).drift.into()
+    }
+
+    fn part2(vals: &Vec<i64>) -> Answer {
+        match first_repeat(vals) {
+            Some(repeat) => repeat.frequency.into(),
+            None => Answer::Unsolved("no repeat".into()),
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        // with fewer possible drifts than changes, two frequencies in the first pass share a
+        // remainder modulo the drift, so some frequency is guaranteed to repeat
+        let size = size.max(2) as i64;
+        let drift = rng.range(-(size - 1)..=size - 1);
+        let mut changes: Vec<i64> = (1..size)
+            .map(|_| rng.range(1..=20) * if rng.chance(0.5) { 1 } else { -1 })
+            .collect();
+        let total: i64 = changes.iter().sum();
+        changes.push(drift - total);
+        let lines: Vec<String> = changes.iter().map(|c| format!("{:+}", c)).collect();
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum(input: &str) -> Answer {
+        Day01::part1(&Day01::parse(input).unwrap())
+    }
+
+    fn repeated_frequency(input: &str) -> Answer {
+        Day01::part2(&Day01::parse(input).unwrap())
+    }
+
+    #[test]
+    fn example_sums() {
+        assert_eq!(sum("+1\n-2\n+3\n+1"), Answer::Int(3));
+        assert_eq!(sum("+1\n+1\n+1"), Answer::Int(3));
+        assert_eq!(sum("+1\n+1\n-2"), Answer::Int(0));
+        assert_eq!(sum("-1\n-2\n-3"), Answer::Int(-6));
+    }
+
+    #[test]
+    fn example_repeats() {
+        assert_eq!(repeated_frequency("+1\n-2\n+3\n+1"), Answer::Int(2));
+        assert_eq!(repeated_frequency("+1\n-1"), Answer::Int(0));
+        assert_eq!(repeated_frequency("+3\n+3\n+4\n-2\n-4"), Answer::Int(10));
+        assert_eq!(repeated_frequency("-6\n+3\n+8\n+5\n-6"), Answer::Int(5));
+        assert_eq!(repeated_frequency("+7\n+7\n-2\n-7\n-4"), Answer::Int(14));
+    }
+
+    #[test]
+    fn generated_input_repeats() {
+        let input = Day01::generate(&mut Rng::new(1), 200).unwrap();
+        let vals = Day01::parse(&input).unwrap();
+        assert_eq!(vals.len(), 200);
+        Day01::part2(&vals);
+    }
+
+    /// Reference for part 2: remember every frequency in a list and search it each time, giving
+    /// up after `passes` passes.
+    fn naive_first_repeat(vals: &[i64], passes: u128) -> Option<Repeat> {
+        let mut seen: Vec<i128> = vec![0];
+        let mut freq = 0;
+        for pass in 0..passes {
+            for (index, v) in vals.iter().enumerate() {
+                freq += i128::from(*v);
+                if seen.contains(&freq) {
+                    return Some(Repeat {
+                        frequency: freq,
+                        pass,
+                        index,
+                    });
+                }
+                seen.push(freq);
+            }
+        }
+        None
+    }
+
+    fn changes(input: &str) -> Vec<i64> {
+        Day01::parse(input).unwrap()
+    }
+
+    #[test]
+    fn reports_where_it_repeats() {
+        let repeat = |input: &str| first_repeat(&changes(input)).unwrap();
+        assert_eq!(
+            repeat("+1\n-2\n+3\n+1"),
+            Repeat {
+                frequency: 2,
+                pass: 1,
+                index: 1
+            }
+        );
+        assert_eq!(
+            repeat("+1\n-1"),
+            Repeat {
+                frequency: 0,
+                pass: 0,
+                index: 1
+            }
+        );
+        assert_eq!(
+            repeat("+7\n+7\n-2\n-7\n-4"),
+            Repeat {
+                frequency: 14,
+                pass: 2,
+                index: 2
+            }
+        );
+        // back to the start at the end of the first pass
+        assert_eq!(
+            repeat("+1\n+2\n-3"),
+            Repeat {
+                frequency: 0,
+                pass: 0,
+                index: 2
+            }
+        );
+    }
+
+    #[test]
+    fn proves_there_is_no_repeat() {
+        assert_eq!(first_repeat(&changes("+1\n+1")), None);
+        assert_eq!(first_repeat(&changes("+3\n-1")), None);
+        assert_eq!(first_repeat(&[]), None);
+        assert_eq!(
+            repeated_frequency("+1\n+1"),
+            Answer::Unsolved("no repeat".into())
+        );
+        // far apart residues need a lot of passes to meet
+        let repeat = first_repeat(&changes("+1000000\n-999999")).unwrap();
+        assert_eq!(repeat.frequency, 1_000_000);
+        assert_eq!((repeat.pass, repeat.index), (999_999, 1));
+    }
+
+    #[test]
+    fn agrees_with_naive_without_a_guaranteed_repeat() {
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let size = 1 + rng.index(12);
+            let vals: Vec<i64> = (0..size).map(|_| rng.range(-10..=10)).collect();
+            let passes = 40;
+            match first_repeat(&vals) {
+                Some(repeat) if repeat.pass < passes => {
+                    assert_eq!(
+                        naive_first_repeat(&vals, passes),
+                        Some(repeat),
+                        "{:?}",
+                        vals
+                    )
+                }
+                Some(_) => {}
+                None => assert_eq!(naive_first_repeat(&vals, passes), None, "{:?}", vals),
+            }
+        }
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..30 {
+            let mut rng = Rng::new(seed);
+            let size = 2 + rng.index(60);
+            let vals = Day01::parse(&Day01::generate(&mut rng, size).unwrap()).unwrap();
+            let total: i64 = vals.iter().sum();
+            assert_eq!(Day01::part1(&vals), Answer::from(total), "seed {}", seed);
+            let repeat = naive_first_repeat(&vals, u128::MAX).unwrap();
+            assert_eq!(first_repeat(&vals), Some(repeat), "seed {}", seed);
+            assert_eq!(
+                Day01::part2(&vals),
+                Answer::from(repeat.frequency),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn follows_the_trajectory() {
+        let trajectory = Trajectory::of(&changes("+3\n+3\n+4\n-2\n-4"));
+        assert_eq!(
+            trajectory,
+            Trajectory {
+                changes: 5,
+                drift: 4,
+                lowest: 0,
+                highest: 10
+            }
+        );
+        assert_eq!(trajectory.range(3), (0, 18));
+        assert_eq!(Trajectory::of(&changes("-5\n+2")).range(4), (-14, 0));
+        assert_eq!(Trajectory::default().range(10), (0, 0));
+    }
+
+    #[test]
+    fn streams_changes() {
+        let read = |text: &str| Trajectory::read(text.as_bytes());
+        let streamed = read("\u{feff}+3\r\n+3\n+4\n-2\n-4\n\n").unwrap();
+        assert_eq!(streamed, Trajectory::of(&changes("+3\n+3\n+4\n-2\n-4")));
+        let error = read("+1\n\n+2").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().starts_with("line 2: "), "{}", error);
+        assert!(read("+1\n+x")
+            .unwrap_err()
+            .to_string()
+            .starts_with("line 2: "));
+    }
+
+    #[test]
+    fn accumulates_without_overflow() {
+        let big = format!("+{}\n+{}\n-1", i64::MAX, i64::MAX);
+        let trajectory = Trajectory::of(&changes(&big));
+        assert_eq!(trajectory.drift, 2 * i128::from(i64::MAX) - 1);
+        assert_eq!(trajectory.highest, 2 * i128::from(i64::MAX));
+        let repeat = first_repeat(&changes(&format!("+{}\n-{}", i64::MAX, i64::MAX - 1))).unwrap();
+        assert_eq!(repeat.frequency, i128::from(i64::MAX));
+        assert_eq!(repeat.pass, i64::MAX as u128 - 1);
+    }
+
+    #[test]
+    fn answers_beyond_i64() {
+        let max = i64::MAX;
+        let sum = Day01::part1(&changes(&format!("+{}\n+{}", max, max)));
+        assert_eq!(sum, Answer::from("18446744073709551614"));
+        assert_eq!(
+            Day01::part1(&changes(&format!("+{}\n-1", max))),
+            Answer::Int(max - 1)
+        );
+        // the start of the second pass comes back to the second frequency of the first
+        let vals = changes(&format!("+{}\n+{}\n+1\n-{}", max, max, max));
+        assert_eq!(Day01::part2(&vals), Answer::from("18446744073709551615"));
+        assert_eq!(
+            naive_first_repeat(&vals, 3).unwrap().frequency,
+            2 * i128::from(max) + 1
+        );
+    }
+
+    /// Reference for [`revisited`]: count every frequency of every pass.
+    fn naive_revisited(vals: &[i64], cycles: u64) -> Vec<(i128, u64)> {
+        let mut visits: BTreeMap<i128, u64> = BTreeMap::new();
+        let mut freq = 0;
+        *visits.entry(freq).or_default() += 1;
+        for _ in 0..cycles {
+            for v in vals {
+                freq += i128::from(*v);
+                *visits.entry(freq).or_default() += 1;
+            }
+        }
+        visits.into_iter().filter(|&(_, n)| n > 1).collect()
+    }
+
+    #[test]
+    fn finds_revisited_frequencies() {
+        assert_eq!(revisited(&changes("+1\n-2\n+3\n+1"), 2), vec![(2, 2)]);
+        assert_eq!(revisited(&changes("+1\n-1"), 3), vec![(0, 4), (1, 3)]);
+        assert_eq!(revisited(&changes("+1\n+1"), 5), vec![]);
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let size = 1 + rng.index(10);
+            let vals: Vec<i64> = (0..size).map(|_| rng.range(-8..=8)).collect();
+            let cycles = rng.index(6) as u64;
+            assert_eq!(
+                revisited(&vals, cycles),
+                naive_revisited(&vals, cycles),
+                "{:?} over {}",
+                vals,
+                cycles
+            );
+        }
+    }
+}
@@ -1,30 +1,29 @@
-use std::collections::HashSet;
+//! Solves both parts, or with `--stream [PATH]` only follows the trajectory of the changes in PATH
+//! (or stdin), however long the list is.
+
+use advent_2018_01::Trajectory;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufReader};
+use std::process;
 
 fn main() {
-    const FNAME: &str = "input.txt";
-    let file = File::open(FNAME).expect(&format!("Couldn't open {}", FNAME));
-    let reader = BufReader::new(&file);
-    let vals: Vec<i32> = reader
-        .lines()
-        .map(|l| {
-            l.expect("Couldn't read line")
-                .parse::<i32>()
-                .expect("Unknown input string")
-        }).collect();
-    // part 1
-    println!("{}", vals.iter().sum::<i32>());
-    // part 2
-    let mut set: HashSet<i32> = HashSet::new();
-    set.insert(0);
-    let part2: Option<i32> = vals
-        .iter()
-        .cycle()
-        .scan(0, |c, v| {
-            *c += v;
-            Some(*c)
-        }).filter(|f| !set.insert(*f))
-        .next();
-    println!("{}", part2.expect("No second element found"));
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some("--stream") {
+        return advent_common::run::<advent_2018_01::Day01>();
+    }
+    let path = args.next().unwrap_or_else(|| "-".to_string());
+    let trajectory = if path == "-" {
+        Trajectory::read(io::stdin().lock())
+    } else {
+        File::open(&path).and_then(|f| Trajectory::read(BufReader::new(f)))
+    };
+    let path = if path == "-" { "<stdin>" } else { &path };
+    let trajectory = trajectory.unwrap_or_else(|e| {
+        eprintln!("Couldn't read {}: {}", path, e);
+        process::exit(1);
+    });
+    println!("changes: {}", trajectory.changes);
+    println!("drift per pass: {}", trajectory.drift);
+    println!("lowest: {}", trajectory.lowest);
+    println!("highest: {}", trajectory.highest);
 }
@@ -0,0 +1,485 @@
+//! Day 2: Inventory Management System.
+//!
+//! ```
+//! use advent_2018_02::{diff, twochars, Day02};
+//! use advent_common::Solution;
+//!
+//! assert!(twochars("bababc"));
+//! assert_eq!(diff("fghij", "fguij"), 1);
+//! let ids = Day02::parse("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz").unwrap();
+//! assert_eq!(Day02::part2(&ids).to_string(), "fgij");
+//! ```
+
+use advent_common::{input, Answer, ParseError, Rng, Solution};
+use std::collections::{BTreeSet, HashMap};
+
+/// How many times each letter appears in `code`.
+pub fn charmap(code: &str) -> HashMap<char, usize> {
+    let mut freq: HashMap<char, usize> = HashMap::new();
+    for c in code.chars() {
+        *freq.entry(c).or_insert(0) += 1;
+    }
+    freq
+}
+
+/// Every number of times some letter appears in `code`.
+pub fn multiplicities(code: &str) -> BTreeSet<usize> {
+    charmap(code).into_values().collect()
+}
+
+/// Whether some letter appears exactly twice.
+pub fn twochars(code: &str) -> bool {
+    multiplicities(code).contains(&2)
+}
+
+/// Whether some letter appears exactly three times.
+pub fn threechars(code: &str) -> bool {
+    multiplicities(code).contains(&3)
+}
+
+/// The product, over each of `wanted`, of how many ids have some letter appearing exactly that
+/// many times. Part 1 wants 2 and 3.
+pub fn checksum(codes: &[String], wanted: &[usize]) -> usize {
+    let mut counts = vec![0; wanted.len()];
+    for code in codes {
+        let present = multiplicities(code);
+        for (count, n) in counts.iter_mut().zip(wanted) {
+            if present.contains(n) {
+                *count += 1;
+            }
+        }
+    }
+    counts.iter().product()
+}
+
+/// Number of positions where the two ids have different letters.
+pub fn diff(code1: &str, code2: &str) -> usize {
+    code1
+        .chars()
+        .zip(code2.chars())
+        .filter(|(c1, c2)| c1 != c2)
+        .count()
+}
+
+/// Two ids that differ in exactly one position, by where they are in the list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pair {
+    pub first: usize,
+    pub second: usize,
+    /// The letters they have in common, in order.
+    pub common: String,
+}
+
+/// Hashes of every prefix of `code`, or of every suffix when `rev`, so the key of the code with
+/// any one position masked out takes constant time.
+fn affix_hashes(code: &[char], rev: bool) -> Vec<u64> {
+    let mut hashes = vec![0u64; code.len() + 1];
+    let mut h = 0u64;
+    for idx in 0..code.len() {
+        let (at, base) = if rev {
+            (code.len() - 1 - idx, 0x0100_0000_01b3)
+        } else {
+            (idx, 0x9e37_79b9_7f4a_7c15)
+        };
+        h = h.wrapping_mul(base).wrapping_add(code[at] as u64 + 1);
+        hashes[if rev { at } else { idx + 1 }] = h;
+    }
+    hashes
+}
+
+/// Every pair of ids of the same length that differ in exactly one position, ordered by the first
+/// id and then the second.
+///
+/// Masking out the position they differ in makes two such ids the same, so for each position the
+/// ids are bucketed by their masked variant and only ids sharing a bucket are compared. That's
+/// about O(n·L) rather than comparing every id with every other.
+pub fn near_pairs(codes: &[String]) -> Vec<Pair> {
+    let codes: Vec<Vec<char>> = codes.iter().map(|c| c.chars().collect()).collect();
+    let prefixes: Vec<Vec<u64>> = codes.iter().map(|c| affix_hashes(c, false)).collect();
+    let suffixes: Vec<Vec<u64>> = codes.iter().map(|c| affix_hashes(c, true)).collect();
+    let longest = codes.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut pairs = Vec::new();
+    let mut buckets: HashMap<(usize, u64, u64), Vec<usize>> = HashMap::new();
+    for at in 0..longest {
+        buckets.clear();
+        for (idx, code) in codes.iter().enumerate() {
+            if at < code.len() {
+                let key = (code.len(), prefixes[idx][at], suffixes[idx][at + 1]);
+                buckets.entry(key).or_default().push(idx);
+            }
+        }
+        for bucket in buckets.values() {
+            for (n, &first) in bucket.iter().enumerate() {
+                for &second in &bucket[n + 1..] {
+                    let (a, b) = (&codes[first], &codes[second]);
+                    // a hash collision could share a bucket too, so check
+                    let same_elsewhere = (0..a.len()).all(|i| i == at || a[i] == b[i]);
+                    if a[at] != b[at] && same_elsewhere {
+                        let mut common = a.clone();
+                        common.remove(at);
+                        pairs.push(Pair {
+                            first,
+                            second,
+                            common: common.into_iter().collect(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    pairs.sort_by_key(|p| (p.first, p.second));
+    pairs
+}
+
+/// Ids that are all linked by ids within some distance of each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    /// Where the ids are in the list, in order.
+    pub members: Vec<usize>,
+    /// The letters every member has in the same position, in order.
+    pub common: String,
+}
+
+/// The root of `idx` in a disjoint set forest, flattening the path to it.
+fn root(parents: &mut [usize], mut idx: usize) -> usize {
+    while parents[idx] != idx {
+        parents[idx] = parents[parents[idx]];
+        idx = parents[idx];
+    }
+    idx
+}
+
+/// The ids of the same length linked by differing in between 1 and `k` positions, grouped with
+/// every id they're linked to through others, ordered by their first member. Ids with nothing
+/// within `k` of them aren't in any cluster.
+///
+/// Cutting ids into `k + 1` pieces, two ids within `k` of each other have to agree on at least
+/// one whole piece, so only ids sharing a piece are compared. That works best with pieces of a
+/// few letters, so `k` well below the length of the ids.
+pub fn clusters(codes: &[String], k: usize) -> Vec<Cluster> {
+    let codes: Vec<Vec<char>> = codes.iter().map(|c| c.chars().collect()).collect();
+    let mut pieces: HashMap<(usize, usize, &[char]), Vec<usize>> = HashMap::new();
+    for (idx, code) in codes.iter().enumerate() {
+        for piece in 0..=k {
+            let (start, end) = (
+                piece * code.len() / (k + 1),
+                (piece + 1) * code.len() / (k + 1),
+            );
+            pieces
+                .entry((code.len(), piece, &code[start..end]))
+                .or_default()
+                .push(idx);
+        }
+    }
+
+    let mut parents: Vec<usize> = (0..codes.len()).collect();
+    let mut linked = vec![false; codes.len()];
+    for ids in pieces.values() {
+        for (n, &first) in ids.iter().enumerate() {
+            for &second in &ids[n + 1..] {
+                let (a, b) = (&codes[first], &codes[second]);
+                let distance = a.iter().zip(b).filter(|(x, y)| x != y).take(k + 1).count();
+                if (1..=k).contains(&distance) {
+                    let (r1, r2) = (root(&mut parents, first), root(&mut parents, second));
+                    parents[r1.max(r2)] = r1.min(r2);
+                    linked[first] = true;
+                    linked[second] = true;
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for idx in (0..codes.len()).filter(|&i| linked[i]) {
+        groups.entry(root(&mut parents, idx)).or_default().push(idx);
+    }
+    let mut clusters: Vec<Cluster> = groups
+        .into_values()
+        .map(|members| {
+            let first = &codes[members[0]];
+            let common = (0..first.len())
+                .filter(|&i| members.iter().all(|&m| codes[m][i] == first[i]))
+                .map(|i| first[i])
+                .collect();
+            Cluster { members, common }
+        })
+        .collect();
+    clusters.sort_by_key(|c| c.members[0]);
+    clusters
+}
+
+fn random_id(rng: &mut Rng) -> String {
+    (0..26)
+        .map(|_| (b'a' + rng.below(26) as u8) as char)
+        .collect()
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 2;
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+        Ok(input::lines(input).map(String::from).collect())
+    }
+
+    fn part1(codes: &Vec<String>) -> Answer {
+        checksum(codes, &[2, 3]).into()
+    }
+
+    fn part2(codes: &Vec<String>) -> Answer {
+        match near_pairs(codes).as_slice() {
+            [] => Answer::Unsolved("no codes differ by a single character".into()),
+            [pair] => pair.common.clone().into(),
+            pairs => {
+                let lines: Vec<String> = pairs
+                    .iter()
+                    .map(|p| format!("lines {} and {}", p.first + 1, p.second + 1))
+                    .collect();
+                Answer::Unsolved(format!(
+                    "ambiguous, {} pairs of codes differ by a single character: {}",
+                    pairs.len(),
+                    lines.join(", ")
+                ))
+            }
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let mut ids: Vec<String> = (1..size.max(2)).map(|_| random_id(rng)).collect();
+        // the prototype fabric is in the one pair of boxes differing by a single letter
+        let mut twin: Vec<u8> = rng.choose(&ids).clone().into_bytes();
+        let at = rng.index(twin.len());
+        twin[at] = b'a' + (twin[at] - b'a' + 1 + rng.below(25) as u8) % 26;
+        ids.push(String::from_utf8(twin).expect("ascii"));
+        rng.shuffle(&mut ids);
+        Some(ids.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_letters() {
+        let freq = charmap("bababc");
+        assert_eq!(freq[&'a'], 2);
+        assert_eq!(freq[&'b'], 3);
+        assert_eq!(freq[&'c'], 1);
+        assert!(twochars("bababc") && threechars("bababc"));
+        assert!(!twochars("abcdef") && !threechars("abcdef"));
+        assert!(twochars("abbcde") && !threechars("abbcde"));
+        assert!(!twochars("ababab") && threechars("ababab"));
+    }
+
+    #[test]
+    fn example_checksum() {
+        let codes = Day02::parse("abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab").unwrap();
+        assert_eq!(Day02::part1(&codes), Answer::Int(12));
+    }
+
+    #[test]
+    fn counts_differences() {
+        assert_eq!(diff("abcde", "axcye"), 2);
+        assert_eq!(diff("fghij", "fguij"), 1);
+        assert_eq!(diff("fghij", "fghij"), 0);
+    }
+
+    #[test]
+    fn example_common_letters() {
+        let codes = Day02::parse("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz").unwrap();
+        assert_eq!(Day02::part2(&codes), Answer::from("fgij"));
+    }
+
+    #[test]
+    fn generated_input_has_a_pair() {
+        let input = Day02::generate(&mut Rng::new(1), 100).unwrap();
+        let codes = Day02::parse(&input).unwrap();
+        assert_eq!(codes.len(), 100);
+        match Day02::part2(&codes) {
+            Answer::Text(common) => assert_eq!(common.len(), 25),
+            other => panic!("unexpected answer {:?}", other),
+        }
+    }
+
+    #[test]
+    fn finds_every_near_pair() {
+        let codes = Day02::parse("abcde\nfghij\nabxde\nfguij\nfghij\nab\nax\nabcd").unwrap();
+        let pairs: Vec<(usize, usize, String)> = near_pairs(&codes)
+            .into_iter()
+            .map(|p| (p.first, p.second, p.common))
+            .collect();
+        // duplicates aren't near each other, and lengths have to match
+        let expected = [(0, 2, "abde"), (1, 3, "fgij"), (3, 4, "fgij"), (5, 6, "a")];
+        let expected: Vec<(usize, usize, String)> = expected
+            .iter()
+            .map(|&(a, b, c)| (a, b, c.to_string()))
+            .collect();
+        assert_eq!(pairs, expected);
+        assert!(near_pairs(&[]).is_empty());
+    }
+
+    #[test]
+    fn checksums_any_multiplicities() {
+        let codes =
+            Day02::parse("abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab\naaaab").unwrap();
+        assert_eq!(checksum(&codes, &[2, 3]), 12);
+        assert_eq!(checksum(&codes, &[2, 3, 4]), 12);
+        assert_eq!(checksum(&codes, &[1]), 7);
+        assert_eq!(checksum(&codes, &[5]), 0);
+        assert_eq!(checksum(&codes, &[]), 1);
+        assert_eq!(
+            multiplicities("aaaabbc").into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 4]
+        );
+        assert_eq!(charmap(&"a".repeat(300))[&'a'], 300);
+    }
+
+    #[test]
+    fn clusters_within_distance() {
+        let codes =
+            Day02::parse("abcde\nfghij\nabxde\nfguij\nfghij\nxbxde\nklmno\nab\nax").unwrap();
+        let found = |k| -> Vec<(Vec<usize>, String)> {
+            clusters(&codes, k)
+                .into_iter()
+                .map(|c| (c.members, c.common))
+                .collect()
+        };
+        assert_eq!(
+            found(1),
+            vec![
+                (vec![0, 2, 5], "bde".to_string()),
+                (vec![1, 3, 4], "fgij".to_string()),
+                (vec![7, 8], "a".to_string()),
+            ]
+        );
+        // at distance 1 the clusters are made of the near pairs
+        let pairs = near_pairs(&codes);
+        assert!(pairs.iter().all(|p| found(1)
+            .iter()
+            .any(|(m, _)| m.contains(&p.first) && m.contains(&p.second))));
+        assert_eq!(found(0), vec![]);
+        assert_eq!(found(5).len(), 2);
+    }
+
+    /// Reference for [`clusters`]: compare every pair and merge groups until nothing changes.
+    fn naive_clusters(codes: &[String], k: usize) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (i, a) in codes.iter().enumerate() {
+            for (j, b) in codes.iter().enumerate().skip(i + 1) {
+                let d = diff(a, b);
+                if a.len() == b.len() && d >= 1 && d <= k {
+                    groups.push(vec![i, j]);
+                }
+            }
+        }
+        loop {
+            let merge = (0..groups.len()).find_map(|x| {
+                ((x + 1)..groups.len())
+                    .find(|&y| groups[y].iter().any(|m| groups[x].contains(m)))
+                    .map(|y| (x, y))
+            });
+            match merge {
+                Some((x, y)) => {
+                    let other = groups.remove(y);
+                    groups[x].extend(other);
+                }
+                None => break,
+            }
+        }
+        for group in &mut groups {
+            group.sort_unstable();
+            group.dedup();
+        }
+        groups.sort();
+        groups
+    }
+
+    #[test]
+    fn clusters_agree_with_naive() {
+        for seed in 0..30 {
+            let mut rng = Rng::new(seed);
+            let codes: Vec<String> = (0..2 + rng.index(30))
+                .map(|_| {
+                    (0..4 + rng.index(2))
+                        .map(|_| (b'a' + rng.below(3) as u8) as char)
+                        .collect()
+                })
+                .collect();
+            for k in 0..4 {
+                let members: Vec<Vec<usize>> =
+                    clusters(&codes, k).into_iter().map(|c| c.members).collect();
+                assert_eq!(members, naive_clusters(&codes, k), "seed {} k {}", seed, k);
+            }
+        }
+    }
+
+    #[test]
+    fn reports_ambiguity() {
+        let codes = Day02::parse("abcde\nabxde\nfghij\nfguij").unwrap();
+        assert_eq!(
+            Day02::part2(&codes),
+            Answer::Unsolved(
+                "ambiguous, 2 pairs of codes differ by a single character: \
+                 lines 1 and 2, lines 3 and 4"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn reports_no_pair() {
+        let codes = Day02::parse("abcde\nfghij\nabcde").unwrap();
+        assert_eq!(
+            Day02::part2(&codes),
+            Answer::Unsolved("no codes differ by a single character".into())
+        );
+    }
+
+    /// Reference for part 1: count each letter by searching the whole code for it.
+    fn naive_checksum(codes: &[String]) -> usize {
+        let has = |code: &str, n: usize| ('a'..='z').any(|c| code.matches(c).count() == n);
+        let twos = codes.iter().filter(|c| has(c, 2)).count();
+        let threes = codes.iter().filter(|c| has(c, 3)).count();
+        twos * threes
+    }
+
+    /// Reference for part 2: cut each position out of every code in turn and look for two codes
+    /// that end up the same.
+    fn naive_common(codes: &[String]) -> Option<String> {
+        for at in 0..codes[0].len() {
+            let mut seen: Vec<String> = Vec::new();
+            for code in codes {
+                let mut cut = code.clone();
+                cut.remove(at);
+                if seen.contains(&cut) {
+                    return Some(cut);
+                }
+                seen.push(cut);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let size = 2 + rng.index(100);
+            let codes = Day02::parse(&Day02::generate(&mut rng, size).unwrap()).unwrap();
+            let checksum = naive_checksum(&codes);
+            assert_eq!(
+                Day02::part1(&codes),
+                Answer::from(checksum),
+                "seed {}",
+                seed
+            );
+            let common = naive_common(&codes).unwrap();
+            assert_eq!(Day02::part2(&codes), Answer::from(common), "seed {}", seed);
+        }
+    }
+}
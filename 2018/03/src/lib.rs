@@ -0,0 +1,464 @@
+//! Day 3: No Matter How You Slice It.
+//!
+//! ```
+//! use advent_2018_03::{intact_claim, overlap, Claim};
+//!
+//! let claims: Vec<Claim> = ["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"]
+//!     .iter()
+//!     .map(|l| l.parse().unwrap())
+//!     .collect();
+//! assert_eq!(overlap(&claims), 4);
+//! assert_eq!(intact_claim(&claims), Some(3));
+//! ```
+
+use advent_common::{input, Answer, FromLine, ParseError, Point, Rng, Solution};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::str::FromStr;
+
+#[derive(Debug, FromLine)]
+#[line("#{id} @ {left},{top}: {width}x{height}")]
+pub struct Claim {
+    pub id: u32,
+    pub left: usize,
+    pub top: usize,
+    pub width: usize,
+    pub height: usize,
+}
+impl Claim {
+    /// Whether the claim lies within a `size` by `size` fabric.
+    pub fn fits(&self, size: usize) -> bool {
+        let right = self.left.checked_add(self.width);
+        let bottom = self.top.checked_add(self.height);
+        right.is_some_and(|r| r <= size) && bottom.is_some_and(|b| b <= size)
+    }
+
+    /// Every square inch the claim covers.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (left, top) = (self.left as i64, self.top as i64);
+        let (width, height) = (self.width as i64, self.height as i64);
+        (top..top + height).flat_map(move |y| (left..left + width).map(move |x| (x, y)))
+    }
+
+    /// Whether the claim covers no square inches at all.
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// How many tiles the claim covers.
+    fn tile_count(&self) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+        let across = |from: usize, length: usize| tile_span(from, length).len() as u128;
+        across(self.left, self.width) * across(self.top, self.height)
+    }
+
+    /// The tiles the claim covers, each with the columns and rows of it that it covers.
+    fn tiles(&self) -> impl Iterator<Item = ((usize, usize), Range<usize>, Range<usize>)> {
+        let across = |from: usize, length: usize| {
+            let to = from + length;
+            tile_span(from, length).map(move |t| {
+                (
+                    t,
+                    from.max(t * TILE) - t * TILE..to.min((t + 1) * TILE) - t * TILE,
+                )
+            })
+        };
+        // an empty claim has no columns, so don't walk its rows either
+        let height = if self.is_empty() { 0 } else { self.height };
+        let columns: Vec<_> = across(self.left, self.width).collect();
+        across(self.top, height).flat_map(move |(y, rows)| {
+            columns
+                .clone()
+                .into_iter()
+                .map(move |(x, cols)| ((x, y), cols, rows.clone()))
+        })
+    }
+}
+/// The tiles along one side covering `length` inches from `from`.
+fn tile_span(from: usize, length: usize) -> Range<usize> {
+    let first = from / TILE;
+    if length == 0 {
+        first..first
+    } else {
+        first..(from + length).div_ceil(TILE)
+    }
+}
+
+/// The puzzle promises the fabric is at least this many inches on each side, which is as big as
+/// generated inputs get.
+pub const FABRIC_SIZE: usize = 1000;
+
+/// Claims can reach as far as a [`Point`] can.
+pub const MAX_EXTENT: usize = i64::MAX as usize;
+
+/// Tiles the claims can cover between them, counting a tile once for every claim on it. This is
+/// what the survey's time and memory grow with, whatever the fabric's size.
+pub const MAX_TILES: u128 = 1 << 18;
+
+/// Side of the square tiles the fabric is looked at in.
+const TILE: usize = 128;
+
+/// Which claim, and the columns and rows of a tile it covers.
+type Piece = (usize, Range<usize>, Range<usize>);
+
+/// Where claims overlap on the fabric.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Survey {
+    /// Square inches covered by more than one claim.
+    pub overlap: usize,
+    /// Whether each claim shares any square inch with another.
+    pub overlapping: Vec<bool>,
+}
+
+/// Find where `claims` overlap, however big the fabric they're on.
+///
+/// The fabric is cut into tiles and claims are grouped by the tiles they cover. Only tiles with
+/// more than one claim can have any overlap, and those are counted one at a time in the same
+/// buffer. Empty parts of the fabric cost nothing, but every tile a claim covers is remembered,
+/// so time and memory still grow with the area the claims cover, about one entry per 128 by 128
+/// inches.
+pub fn survey(claims: &[Claim]) -> Survey {
+    // the piece of each claim in each tile it covers
+    let mut tiles: HashMap<(usize, usize), Vec<Piece>> = HashMap::new();
+    for (idx, claim) in claims.iter().enumerate() {
+        for (tile, cols, rows) in claim.tiles() {
+            tiles.entry(tile).or_default().push((idx, cols, rows));
+        }
+    }
+
+    let mut survey = Survey {
+        overlap: 0,
+        overlapping: vec![false; claims.len()],
+    };
+    let mut counts = vec![0u32; TILE * TILE];
+    for parts in tiles.values().filter(|parts| parts.len() > 1) {
+        let cells = |cols: &Range<usize>, rows: &Range<usize>| {
+            let (cols, rows) = (cols.clone(), rows.clone());
+            rows.flat_map(move |y| cols.clone().map(move |x| y * TILE + x))
+        };
+        for (_, cols, rows) in parts {
+            for cell in cells(cols, rows) {
+                counts[cell] += 1;
+            }
+        }
+        for (idx, cols, rows) in parts {
+            if cells(cols, rows).any(|cell| counts[cell] > 1) {
+                survey.overlapping[*idx] = true;
+            }
+        }
+        // clearing each square as it's counted leaves the buffer ready for the next tile, and
+        // keeps squares several claims share from being counted more than once
+        for (_, cols, rows) in parts {
+            for cell in cells(cols, rows) {
+                if counts[cell] > 1 {
+                    survey.overlap += 1;
+                }
+                counts[cell] = 0;
+            }
+        }
+    }
+    survey
+}
+
+/// Square inches covered by more than one claim.
+pub fn overlap(claims: &[Claim]) -> usize {
+    survey(claims).overlap
+}
+
+/// The id of the first claim that doesn't overlap any other.
+pub fn intact_claim(claims: &[Claim]) -> Option<u32> {
+    survey(claims).intact_claim(claims)
+}
+
+impl Survey {
+    /// The id of the first of the surveyed `claims` that doesn't overlap any other.
+    pub fn intact_claim(&self, claims: &[Claim]) -> Option<u32> {
+        claims
+            .iter()
+            .zip(&self.overlapping)
+            .find(|(_, overlapping)| !**overlapping)
+            .map(|(c, _)| c.id)
+    }
+}
+
+/// The claims, surveyed once for both parts.
+#[derive(Debug)]
+pub struct Fabric {
+    pub claims: Vec<Claim>,
+    pub survey: Survey,
+}
+
+/// A random claim's left, top, width and height on the full size fabric.
+fn random_rect(rng: &mut Rng) -> (usize, usize, usize, usize) {
+    let width = rng.range(1..=30) as usize;
+    let height = rng.range(1..=30) as usize;
+    let left = rng.index(FABRIC_SIZE - width + 1);
+    let top = rng.index(FABRIC_SIZE - height + 1);
+    (left, top, width, height)
+}
+
+fn rects_overlap(a: (usize, usize, usize, usize), b: (usize, usize, usize, usize)) -> bool {
+    a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 3;
+    type Input = Fabric;
+
+    fn parse(input: &str) -> Result<Fabric, ParseError> {
+        let tiles = Cell::new(0u128);
+        let claims = input::parse_lines(input, |l| {
+            let claim = Claim::from_str(l)?;
+            if claim.is_empty() {
+                return Err(ParseError::new(l, "a claim at least 1x1"));
+            }
+            if !claim.fits(MAX_EXTENT) {
+                return Err(ParseError::new(l, "a claim whose far edges fit in an i64"));
+            }
+            tiles.set(tiles.get() + claim.tile_count());
+            if tiles.get() > MAX_TILES {
+                return Err(ParseError::new(
+                    l,
+                    "claims covering at most 2^18 tiles of 128x128 inches in all",
+                ));
+            }
+            Ok(claim)
+        })?;
+        let survey = survey(&claims);
+        Ok(Fabric { claims, survey })
+    }
+
+    fn part1(fabric: &Fabric) -> Answer {
+        fabric.survey.overlap.into()
+    }
+
+    fn part2(fabric: &Fabric) -> Answer {
+        match fabric.survey.intact_claim(&fabric.claims) {
+            Some(id) => id.into(),
+            None => Answer::Unsolved("every claim overlaps another".into()),
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        // each claim is at most 30 inches across, so covers at most 2 by 2 tiles, and parse only
+        // takes so many tiles in all
+        let size = size.clamp(1, MAX_TILES as usize / 4);
+        // keep one claim clear of every other so part 2 has an answer
+        let intact = rng.index(size);
+        let clear = random_rect(rng);
+        let lines: Vec<String> = (0..size)
+            .map(|idx| {
+                let (left, top, width, height) = if idx == intact {
+                    clear
+                } else {
+                    loop {
+                        let rect = random_rect(rng);
+                        if !rects_overlap(rect, clear) {
+                            break rect;
+                        }
+                    }
+                };
+                format!("#{} @ {},{}: {}x{}", idx + 1, left, top, width, height)
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const EXAMPLE: &str = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2";
+
+    #[test]
+    fn parses_claim() {
+        let claim: Claim = "#123 @ 3,2: 5x4".parse().unwrap();
+        assert_eq!(claim.id, 123);
+        let points: Vec<Point> = claim.points().collect();
+        assert_eq!(points.len(), 20);
+        assert_eq!(points.first(), Some(&(3, 2)));
+        assert_eq!(points.last(), Some(&(7, 5)));
+    }
+
+    #[test]
+    fn example_overlap() {
+        let claims = Day03::parse(EXAMPLE).unwrap().claims;
+        assert_eq!(overlap(&claims), 4);
+    }
+
+    #[test]
+    fn example_intact_claim() {
+        let claims = Day03::parse(EXAMPLE).unwrap().claims;
+        assert_eq!(intact_claim(&claims), Some(3));
+        let fabric = Day03::parse(EXAMPLE).unwrap();
+        assert_eq!(Day03::part1(&fabric), Answer::Int(4));
+        assert_eq!(Day03::part2(&fabric), Answer::Int(3));
+    }
+
+    #[test]
+    fn generated_input_has_an_intact_claim() {
+        let input = Day03::generate(&mut Rng::new(1), 500).unwrap();
+        let claims = Day03::parse(&input).unwrap().claims;
+        assert_eq!(claims.len(), 500);
+        assert!(intact_claim(&claims).is_some());
+        let input = Day03::generate(&mut Rng::new(1), usize::MAX).unwrap();
+        assert!(Day03::parse(&input).is_ok());
+    }
+
+    /// The left, top, right and bottom edges (exclusive) shared by two claims.
+    fn intersection(a: &Claim, b: &Claim) -> Option<(usize, usize, usize, usize)> {
+        let (left, right) = (a.left.max(b.left), (a.left + a.width).min(b.left + b.width));
+        let (top, bottom) = (a.top.max(b.top), (a.top + a.height).min(b.top + b.height));
+        if left < right && top < bottom {
+            Some((left, top, right, bottom))
+        } else {
+            None
+        }
+    }
+
+    /// Reference for part 1: intersect every pair of claims and collect the shared squares.
+    fn naive_overlap(claims: &[Claim]) -> usize {
+        let mut shared: HashSet<(usize, usize)> = HashSet::new();
+        for (idx, a) in claims.iter().enumerate() {
+            for b in &claims[idx + 1..] {
+                if let Some((left, top, right, bottom)) = intersection(a, b) {
+                    for x in left..right {
+                        for y in top..bottom {
+                            shared.insert((x, y));
+                        }
+                    }
+                }
+            }
+        }
+        shared.len()
+    }
+
+    /// Reference for part 2: the first claim that intersects no other.
+    fn naive_intact(claims: &[Claim]) -> Option<u32> {
+        claims
+            .iter()
+            .find(|a| {
+                claims
+                    .iter()
+                    .all(|b| a.id == b.id || intersection(a, b).is_none())
+            })
+            .map(|c| c.id)
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..10 {
+            let mut rng = Rng::new(seed);
+            let size = 1 + rng.index(300);
+            let claims = Day03::parse(&Day03::generate(&mut rng, size).unwrap())
+                .unwrap()
+                .claims;
+            let expected = naive_overlap(&claims);
+            assert_eq!(overlap(&claims), expected, "seed {}", seed);
+            let intact = naive_intact(&claims);
+            assert_eq!(intact_claim(&claims), intact, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn rejects_claims_past_any_fabric() {
+        assert!(Day03::parse("#1 @ 999,0: 2x1").is_ok());
+        assert!(Day03::parse("#4000000000 @ 5000000,7000000: 30x30").is_ok());
+        let huge = format!("#1 @ {},0: 1x1", usize::MAX);
+        assert!(Day03::parse(&huge).is_err());
+        let past = format!("#1 @ {},0: 1x1", MAX_EXTENT);
+        assert!(Day03::parse(&past).is_err());
+        assert!(Day03::parse("#1 @ 0,0: 65536x65536").is_ok());
+        let error = Day03::parse("#1 @ 0,0: 65536x65536\n#2 @ 5,5: 1x1").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(Day03::parse("#1 @ 0,0: 4294967296x30").is_err());
+        assert!(Day03::parse("#1 @ 0,0: 1x4294967296").is_err());
+        // one tile for each claim, however few inches of it they cover
+        let corners: Vec<String> = (0..=MAX_TILES)
+            .map(|idx| format!("#{} @ {},0: 1x1", idx + 1, idx as usize * TILE))
+            .collect();
+        assert!(Day03::parse(&corners[1..].join("\n")).is_ok());
+        assert_eq!(
+            Day03::parse(&corners.join("\n")).unwrap_err().line,
+            MAX_TILES as usize + 1
+        );
+    }
+
+    #[test]
+    fn rejects_empty_claims() {
+        let long = "#1 @ 0,0: 0x4611686018427387903";
+        assert!(Day03::parse(long).is_err());
+        assert!(Day03::parse("#1 @ 0,0: 3x0").is_err());
+        // surveying one anyway doesn't walk its rows
+        let claims = vec![long.parse::<Claim>().unwrap()];
+        assert_eq!(claims[0].tiles().count(), 0);
+        assert_eq!(survey(&claims).overlap, 0);
+    }
+
+    #[test]
+    fn reports_no_intact_claim() {
+        let fabric = Day03::parse("#1 @ 0,0: 2x2\n#2 @ 0,0: 1x1").unwrap();
+        let none = Answer::Unsolved("every claim overlaps another".into());
+        assert_eq!(Day03::part2(&fabric), none);
+        assert_eq!(Day03::part2(&Day03::parse("").unwrap()), none);
+    }
+
+    #[test]
+    fn covers_claims_across_tiles() {
+        let claims: Vec<Claim> = ["#1 @ 120,120: 20x20", "#2 @ 127,0: 2x300", "#3 @ 0,0: 0x0"]
+            .iter()
+            .map(|l| l.parse().unwrap())
+            .collect();
+        let parts: Vec<_> = claims[0].tiles().collect();
+        assert_eq!(
+            parts,
+            vec![
+                ((0, 0), 120..128, 120..128),
+                ((1, 0), 0..12, 120..128),
+                ((0, 1), 120..128, 0..12),
+                ((1, 1), 0..12, 0..12),
+            ]
+        );
+        assert_eq!(claims[2].tiles().count(), 0);
+        assert_eq!(overlap(&claims), naive_overlap(&claims));
+        assert_eq!(overlap(&claims), 40);
+        assert_eq!(intact_claim(&claims), Some(3));
+    }
+
+    #[test]
+    fn surveys_big_claims_quickly() {
+        let claims: Vec<Claim> = ["#1 @ 0,0: 20000x20000", "#2 @ 19999,19999: 5x5"]
+            .iter()
+            .map(|l| l.parse().unwrap())
+            .collect();
+        let survey = survey(&claims);
+        assert_eq!(survey.overlap, 1);
+        assert_eq!(survey.overlapping, vec![true, true]);
+    }
+
+    #[test]
+    fn surveys_huge_sparse_fabrics() {
+        let mut rng = Rng::new(7);
+        let claims: Vec<Claim> = (0..2000)
+            .map(|idx| Claim {
+                id: 1_000_000 + idx,
+                left: rng.index(5_000_000),
+                top: rng.index(5_000_000),
+                width: 1 + rng.index(400),
+                height: 1 + rng.index(400),
+            })
+            .collect();
+        let survey = survey(&claims);
+        assert_eq!(survey.overlap, naive_overlap(&claims));
+        assert_eq!(intact_claim(&claims), naive_intact(&claims));
+        assert!(survey.overlapping.iter().any(|o| !o));
+    }
+}
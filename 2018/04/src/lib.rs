@@ -0,0 +1,370 @@
+//! Day 4: Repose Record.
+//!
+//! ```
+//! use advent_2018_04::Day04;
+//! use advent_common::Solution;
+//!
+//! let log = "[1518-11-01 00:00] Guard #10 begins shift
+//! [1518-11-01 00:05] falls asleep
+//! [1518-11-01 00:25] wakes up
+//! [1518-11-02 00:00] Guard #10 begins shift
+//! [1518-11-02 00:24] falls asleep
+//! [1518-11-02 00:26] wakes up";
+//! let guards = Day04::parse(log).unwrap();
+//! assert_eq!(guards[0].total, 22);
+//! assert_eq!(guards[0].most_asleep(), (24, 2));
+//! ```
+
+use advent_common::{input, Answer, FromLine, ParseError, Rng, Solution};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// One line of the log. The stamp is everything before the minute, which is all the sorting
+/// needs.
+#[derive(Debug, FromLine)]
+#[line("[{stamp}:{minute}] {event}")]
+struct Record {
+    stamp: String,
+    minute: u8,
+    event: String,
+}
+
+#[derive(Debug, FromLine)]
+#[line("Guard #{id} begins shift")]
+struct ShiftStart {
+    id: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Shift {
+    id: u32,
+    sleeping: Vec<Range<u8>>,
+}
+
+/// Group the chronologically sorted log into shifts. Each record comes with its line number in
+/// the unsorted input, so errors still point at the right place.
+fn parse_shifts(records: &[(usize, Record)]) -> Result<Vec<Shift>, ParseError> {
+    let mut shifts: Vec<Shift> = Vec::new();
+    let mut guard: u32 = 0;
+    let mut start: u8 = 0;
+    let mut sleeping: Vec<Range<u8>> = Vec::new();
+    for (idx, record) in records {
+        match record.event.as_str() {
+            "falls asleep" => start = record.minute,
+            "wakes up" => sleeping.push(start..record.minute),
+            event if event.starts_with("Guard") => {
+                let shift: ShiftStart = event.parse().map_err(|e: ParseError| e.at_line(*idx))?;
+                if !sleeping.is_empty() {
+                    shifts.push(Shift {
+                        id: guard,
+                        sleeping: sleeping.clone(),
+                    });
+                    sleeping.clear();
+                }
+                guard = shift.id;
+            }
+            event => {
+                return Err(ParseError::new(
+                    event,
+                    "a guard beginning a shift, falling asleep or waking up",
+                )
+                .at_line(*idx))
+            }
+        }
+    }
+    // the last shift has no guard after it to close it off
+    if !sleeping.is_empty() {
+        shifts.push(Shift {
+            id: guard,
+            sleeping,
+        });
+    }
+    Ok(shifts)
+}
+
+#[derive(Debug, Clone)]
+/// Everything the log says about one guard's sleep over all their shifts.
+pub struct Guard {
+    pub id: u32,
+    /// Minutes asleep in total.
+    pub total: u32,
+    /// How many shifts the guard spent asleep during each minute past midnight.
+    pub time_asleep: HashMap<u8, u32>,
+}
+impl Guard {
+    /// The minute the guard was most often asleep, and how many times.
+    pub fn most_asleep(&self) -> (u8, u32) {
+        let mut val: u32 = 0;
+        let mut min: u8 = 0;
+        for (m, v) in &self.time_asleep {
+            if v > &val {
+                val = *v;
+                min = *m;
+            }
+        }
+        (min, val)
+    }
+}
+
+fn combine_shifts(shifts: Vec<Shift>) -> Vec<Guard> {
+    let mut guards: Vec<Guard> = Vec::new();
+    let mut shifts_per_guard: HashMap<u32, Vec<Shift>> = HashMap::new();
+    for shift in &shifts {
+        let id: u32 = shift.id;
+        shifts_per_guard.entry(id).or_default().push(shift.clone());
+    }
+    for (id, id_shifts) in &shifts_per_guard {
+        let mut total: u32 = 0;
+        let mut time_asleep: HashMap<u8, u32> = HashMap::new();
+        for shift in id_shifts {
+            for sleep in &shift.sleeping {
+                for m in sleep.clone() {
+                    total += 1;
+                    *time_asleep.entry(m).or_insert(0) += 1;
+                }
+            }
+        }
+        guards.push(Guard {
+            id: *id,
+            total,
+            time_asleep,
+        });
+    }
+    guards
+}
+
+const MONTH_DAYS: [i64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// The date `n` days after the start of 1518, ignoring leap years.
+fn date(n: i64) -> String {
+    let year = 1518 + n / 365;
+    let mut day = n % 365;
+    let mut month = 0;
+    while day >= MONTH_DAYS[month] {
+        day -= MONTH_DAYS[month];
+        month += 1;
+    }
+    format!("{}-{:02}-{:02}", year, month + 1, day + 1)
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 4;
+    type Input = Vec<Guard>;
+
+    fn parse(input: &str) -> Result<Vec<Guard>, ParseError> {
+        let mut records: Vec<(usize, Record)> = input::parse_lines(input, |l| l.parse())?
+            .into_iter()
+            .enumerate()
+            .map(|(idx, r)| (idx + 1, r))
+            .collect();
+        records.sort_by(|(_, a), (_, b)| (&a.stamp, a.minute).cmp(&(&b.stamp, b.minute)));
+        let shifts: Vec<Shift> = parse_shifts(records.as_slice())?;
+        if shifts.is_empty() {
+            return Err(ParseError::new(
+                input::first_line(input),
+                "a log with at least one guard falling asleep",
+            ));
+        }
+        Ok(combine_shifts(shifts))
+    }
+
+    fn part1(guards: &Vec<Guard>) -> Answer {
+        let max_time: &Guard = guards.iter().max_by_key(|x| x.total).unwrap();
+        (u64::from(max_time.id) * u64::from(max_time.most_asleep().0)).into()
+    }
+
+    fn part2(guards: &Vec<Guard>) -> Answer {
+        let max_minute: &Guard = guards.iter().max_by_key(|x| x.most_asleep().1).unwrap();
+        (u64::from(max_minute.id) * u64::from(max_minute.most_asleep().0)).into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let shifts = size.max(1);
+        let guards: Vec<i64> = (0..(shifts / 8).clamp(1, 30))
+            .map(|_| rng.range(1..=3499))
+            .collect();
+        let mut lines: Vec<String> = Vec::new();
+        for shift in 0..shifts {
+            let today = date(shift as i64 + 1);
+            // guards sometimes turn up just before midnight
+            let begins = if rng.chance(0.3) {
+                format!("{} 23:{:02}", date(shift as i64), rng.range(45..=59))
+            } else {
+                format!("{} 00:{:02}", today, rng.range(0..=5))
+            };
+            lines.push(format!(
+                "[{}] Guard #{} begins shift",
+                begins,
+                rng.choose(&guards)
+            ));
+            // the first shift always has a nap so there's a sleepiest guard
+            let naps = if shift == 0 { 1 } else { rng.index(4) };
+            let mut minutes: Vec<i64> = (6..60).collect();
+            rng.shuffle(&mut minutes);
+            let mut minutes = minutes[..2 * naps].to_vec();
+            minutes.sort_unstable();
+            for nap in minutes.chunks(2) {
+                lines.push(format!("[{} 00:{:02}] falls asleep", today, nap[0]));
+                lines.push(format!("[{} 00:{:02}] wakes up", today, nap[1]));
+            }
+        }
+        rng.shuffle(&mut lines);
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // deliberately out of order, the log has to be sorted by timestamp first
+    const EXAMPLE: &str = "\
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up";
+
+    #[test]
+    fn parses_record() {
+        let record: Record = "[1518-11-01 00:05] falls asleep".parse().unwrap();
+        assert_eq!(record.stamp, "1518-11-01 00");
+        assert_eq!(record.minute, 5);
+        assert_eq!(record.event, "falls asleep");
+    }
+
+    #[test]
+    fn example_most_asleep() {
+        let guards = Day04::parse(EXAMPLE).unwrap();
+        assert_eq!(Day04::part1(&guards), Answer::Int(240));
+    }
+
+    #[test]
+    fn example_most_frequent_minute() {
+        let guards = Day04::parse(EXAMPLE).unwrap();
+        assert_eq!(Day04::part2(&guards), Answer::Int(4455));
+    }
+
+    #[test]
+    fn reports_unsorted_line_number() {
+        let err =
+            Day04::parse("[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] dozes")
+                .unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn generated_input_solves() {
+        let input = Day04::generate(&mut Rng::new(1), 400).unwrap();
+        let guards = Day04::parse(&input).unwrap();
+        assert!(!guards.is_empty());
+        Day04::part1(&guards);
+        Day04::part2(&guards);
+    }
+
+    #[test]
+    fn dates_roll_over() {
+        assert_eq!(date(0), "1518-01-01");
+        assert_eq!(date(31), "1518-02-01");
+        assert_eq!(date(364), "1518-12-31");
+        assert_eq!(date(365), "1519-01-01");
+    }
+
+    /// Every key sharing the largest count.
+    fn maxima<K>(items: impl IntoIterator<Item = (K, u32)>) -> Vec<K> {
+        let mut best: Vec<(K, u32)> = Vec::new();
+        for (key, count) in items {
+            match best.first() {
+                Some(&(_, top)) if count < top => {}
+                Some(&(_, top)) if count == top => best.push((key, count)),
+                _ => best = vec![(key, count)],
+            }
+        }
+        best.into_iter().map(|(key, _)| key).collect()
+    }
+
+    /// Reference for both parts: tally every guard and minute straight from the sorted log,
+    /// without grouping it into shifts. Ties make several answers equally right, so this returns
+    /// all of them.
+    fn naive_strategies(input: &str) -> (Vec<u32>, Vec<u32>) {
+        let mut lines: Vec<&str> = input.lines().collect();
+        lines.sort_unstable();
+        let mut asleep: HashMap<(u32, u32), u32> = HashMap::new();
+        let (mut guard, mut start) = (0, 0);
+        for line in lines {
+            let minute: u32 = line[15..17].parse().unwrap();
+            if let Some(rest) = line.split('#').nth(1) {
+                guard = rest.split(' ').next().unwrap().parse().unwrap();
+            } else if line.ends_with("falls asleep") {
+                start = minute;
+            } else {
+                for m in start..minute {
+                    *asleep.entry((guard, m)).or_insert(0) += 1;
+                }
+            }
+        }
+        let mut totals: HashMap<u32, u32> = HashMap::new();
+        for (&(guard, _), count) in &asleep {
+            *totals.entry(guard).or_insert(0) += count;
+        }
+        let mut part1 = Vec::new();
+        for sleepiest in maxima(totals) {
+            let minutes = asleep
+                .iter()
+                .filter(|((guard, _), _)| *guard == sleepiest)
+                .map(|(&(_, minute), &count)| (minute, count));
+            part1.extend(maxima(minutes).into_iter().map(|minute| sleepiest * minute));
+        }
+        let part2 = maxima(asleep)
+            .into_iter()
+            .map(|(guard, minute)| guard * minute)
+            .collect();
+        (part1, part2)
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..40 {
+            let mut rng = Rng::new(seed);
+            let size = 1 + rng.index(200);
+            let input = Day04::generate(&mut rng, size).unwrap();
+            let guards = Day04::parse(&input).unwrap();
+            let (part1, part2) = naive_strategies(&input);
+            let answer = |a: Answer| match a {
+                Answer::Int(n) => n as u32,
+                other => panic!("unexpected answer {:?}", other),
+            };
+            let strategy1 = answer(Day04::part1(&guards));
+            assert!(
+                part1.contains(&strategy1),
+                "seed {}: {} not in {:?}",
+                seed,
+                strategy1,
+                part1
+            );
+            let strategy2 = answer(Day04::part2(&guards));
+            assert!(
+                part2.contains(&strategy2),
+                "seed {}: {} not in {:?}",
+                seed,
+                strategy2,
+                part2
+            );
+        }
+    }
+}
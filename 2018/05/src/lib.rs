@@ -0,0 +1,181 @@
+//! Day 5: Alchemical Reduction.
+//!
+//! ```
+//! use advent_2018_05::process_reaction;
+//!
+//! let polymer: Vec<char> = "dabAcCaCBAcCcaDA".chars().collect();
+//! assert_eq!(process_reaction(&polymer).len(), 10);
+//! ```
+
+use advent_common::{Answer, ParseError, Rng, Solution};
+use std::collections::HashSet;
+use std::iter::FromIterator;
+
+/// Whether two adjacent units destroy each other: the same type with opposite polarities.
+pub fn is_reacting(a: char, b: char) -> bool {
+    // apparently aa doens't react
+    a.eq_ignore_ascii_case(&b) && a != b
+}
+
+/// What is left of the polymer once every reaction has happened.
+pub fn process_reaction(chars: &[char]) -> Vec<char> {
+    chars.iter().fold(Vec::new(), |mut new_chars, c| {
+        if is_reacting(*c, *new_chars.last().unwrap_or(&'!')) {
+            new_chars.pop();
+        } else {
+            new_chars.push(*c);
+        }
+        new_chars
+    })
+}
+
+/// The shortest fully reacted polymer after taking out every unit of a single type.
+pub fn collapsed_reaction(chars: &[char]) -> Vec<char> {
+    let units: HashSet<char> = HashSet::from_iter(chars.iter().map(|c| c.to_ascii_lowercase()));
+    units
+        .iter()
+        .map(|u| {
+            let collapsed: Vec<char> =
+                Vec::from_iter(chars.iter().cloned().filter(|x| !u.eq_ignore_ascii_case(x)));
+            process_reaction(collapsed.as_slice())
+        })
+        .min_by_key(|r| r.len())
+        // nothing left to take out of a polymer that reacted away completely
+        .unwrap_or_default()
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 5;
+    type Input = Vec<char>;
+
+    fn parse(input: &str) -> Result<Vec<char>, ParseError> {
+        let line = input.trim();
+        if line.is_empty() || !line.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(ParseError::new(line, "a polymer of ascii letters").at_line(1));
+        }
+        Ok(Vec::from_iter(line.chars()))
+    }
+
+    fn part1(chars: &Vec<char>) -> Answer {
+        process_reaction(chars.as_slice()).len().into()
+    }
+
+    fn part2(chars: &Vec<char>) -> Answer {
+        // removing a unit can't stop the rest from reacting, so start from the reacted polymer
+        let reacted = process_reaction(chars.as_slice());
+        collapsed_reaction(reacted.as_slice()).len().into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let polymer: String = (0..size.max(1))
+            .map(|_| {
+                let unit = (b'a' + rng.below(26) as u8) as char;
+                if rng.chance(0.5) {
+                    unit.to_ascii_uppercase()
+                } else {
+                    unit
+                }
+            })
+            .collect();
+        Some(polymer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polymer(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn reacting_units() {
+        assert!(is_reacting('a', 'A'));
+        assert!(is_reacting('A', 'a'));
+        assert!(!is_reacting('a', 'a'));
+        assert!(!is_reacting('a', 'B'));
+    }
+
+    #[test]
+    fn example_reaction() {
+        let reacted = process_reaction(&polymer("dabAcCaCBAcCcaDA"));
+        assert_eq!(reacted, polymer("dabCBAcaDA"));
+        assert_eq!(process_reaction(&polymer("aA")), polymer(""));
+        assert_eq!(process_reaction(&polymer("abBA")), polymer(""));
+        assert_eq!(process_reaction(&polymer("abAB")), polymer("abAB"));
+        assert_eq!(process_reaction(&polymer("aabAAB")), polymer("aabAAB"));
+    }
+
+    #[test]
+    fn example_collapsed() {
+        let collapsed = collapsed_reaction(&polymer("dabAcCaCBAcCcaDA"));
+        assert_eq!(collapsed, polymer("daDA"));
+    }
+
+    #[test]
+    fn generated_input_reacts() {
+        let input = Day05::generate(&mut Rng::new(1), 2000).unwrap();
+        let chars = Day05::parse(&input).unwrap();
+        assert_eq!(chars.len(), 2000);
+        assert!(process_reaction(&chars).len() < 2000);
+    }
+
+    /// Reference for part 1: find the first pair that reacts and cut it out, until none do.
+    fn naive_react(polymer: &str) -> String {
+        let mut polymer = polymer.to_string();
+        loop {
+            let units = polymer.as_bytes();
+            let reacting = (1..units.len()).find(|&i| {
+                units[i - 1] != units[i] && units[i - 1].eq_ignore_ascii_case(&units[i])
+            });
+            match reacting {
+                Some(i) => polymer.replace_range(i - 1..=i, ""),
+                None => return polymer,
+            }
+        }
+    }
+
+    /// Reference for part 2: take out each letter of the alphabet from the original polymer.
+    fn naive_shortest(polymer: &str) -> usize {
+        ('a'..='z')
+            .map(|u| naive_react(&polymer.replace(|c: char| c.eq_ignore_ascii_case(&u), "")).len())
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..40 {
+            let mut rng = Rng::new(seed);
+            // a handful of unit types react far more often than the whole alphabet does
+            let len = 1 + rng.index(200);
+            let polymer: String = (0..len)
+                .map(|_| *rng.choose(&['a', 'A', 'b', 'B', 'c', 'C']))
+                .collect();
+            let generated = Day05::generate(&mut rng, 300).unwrap();
+            for polymer in [polymer, generated].iter() {
+                let chars = Day05::parse(polymer).unwrap();
+                let reacted = naive_react(polymer).len();
+                assert_eq!(Day05::part1(&chars), Answer::from(reacted), "seed {}", seed);
+                let shortest = naive_shortest(polymer);
+                assert_eq!(
+                    Day05::part2(&chars),
+                    Answer::from(shortest),
+                    "seed {}",
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fully_reacting_polymer() {
+        let chars = Day05::parse("abBA").unwrap();
+        assert_eq!(Day05::part1(&chars), Answer::Int(0));
+        assert_eq!(Day05::part2(&chars), Answer::Int(0));
+    }
+}
@@ -0,0 +1,304 @@
+//! Day 6: Chronal Coordinates.
+//!
+//! ```
+//! use advent_2018_06::Day06;
+//! use advent_common::Solution;
+//!
+//! let coords = Day06::parse("1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9").unwrap();
+//! assert_eq!(Day06::part1(&coords).to_string(), "17");
+//! assert_eq!(advent_2018_06::safe_region(&coords, 32), 16);
+//! ```
+
+use advent_common::{error, input, Answer, Grid, ParseError, Point, Rng, Solution};
+use std::collections::{HashMap, HashSet};
+use std::iter::FromIterator;
+
+#[derive(Debug, Clone)]
+pub struct Coordinate {
+    /// Position in the input, counting from 1 so 0 can stand for a tie.
+    pub id: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Coordinate {
+    /// Manhattan distance to `point`.
+    pub fn distance(&self, (x, y): Point) -> u32 {
+        ((self.x as i64 - x).abs() + (self.y as i64 - y).abs()) as u32
+    }
+}
+
+/// Real coordinates are all below 400. Capping them keeps the board a sensible size whatever the
+/// input says.
+const MAX_COORDINATE: u32 = 2000;
+
+/// A board reaching from the origin to the furthest coordinates.
+pub fn board(input: &[Coordinate]) -> Grid<u32> {
+    let width = input.iter().map(|c| c.x).max().expect("No inputs?");
+    let height = input.iter().map(|c| c.y).max().expect("No inputs?");
+    Grid::new(width as usize + 1, height as usize + 1, 0)
+}
+
+/// The id of the only coordinate closest to `point`, or 0 for a tie.
+pub fn nearest(point: Point, coords: &[Coordinate]) -> u32 {
+    let dists: Vec<(u32, Coordinate)> = coords
+        .iter()
+        .map(|c| (c.distance(point), c.clone()))
+        .collect();
+    let mindist = dists
+        .iter()
+        .min_by_key(|(d, _)| d)
+        .map(|(d, _)| d)
+        .expect("no min");
+    if dists.iter().filter(|(d, _)| d == mindist).count() > 1 {
+        0u32
+    } else {
+        dists
+            .iter()
+            .min_by_key(|(d, _)| d)
+            .map(|(_, v)| v.id)
+            .expect("no min2")
+    }
+}
+
+/// Sum of the distances from `point` to every coordinate.
+pub fn total(point: Point, coords: &[Coordinate]) -> u64 {
+    coords
+        .iter()
+        .map(|c| u64::from(c.distance(point)))
+        .sum::<u64>()
+}
+
+/// Coordinates owning a cell on the edge of the board, whose areas go on forever.
+pub fn ids_on_edge(board: &Grid<u32>) -> HashSet<u32> {
+    // if an edge is closest, it'll be infinite since the nearest will be the same going in that
+    // direction.
+    board.edges().map(|(_, id)| *id).collect()
+}
+
+/// Size of every finite area on the board.
+pub fn counts(board: &Grid<u32>, infinite: &HashSet<u32>) -> HashMap<u32, u32> {
+    let mut count: HashMap<u32, u32> = HashMap::new();
+    for v in board.values().filter(|v| !infinite.contains(v)) {
+        *count.entry(*v).or_insert(0) += 1;
+    }
+    count
+}
+
+/// Number of cells whose total distance to every coordinate is below `limit`.
+pub fn safe_region(coords: &[Coordinate], limit: u32) -> u32 {
+    // every step off the board adds one to the distance to every coordinate, so nothing more
+    // than limit / n steps out can be safe
+    let margin = i64::from(limit) / coords.len() as i64;
+    let bounds = board(coords).bounds().grow(margin);
+    bounds
+        .points()
+        .filter(|&p| total(p, coords) < u64::from(limit))
+        .count() as u32
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 6;
+    type Input = Vec<Coordinate>;
+
+    fn parse(input: &str) -> Result<Vec<Coordinate>, ParseError> {
+        let points = input::parse_lines(input, |l| {
+            let mut itr = l.splitn(2, ", ");
+            match (itr.next(), itr.next()) {
+                (Some(x), Some(y)) => {
+                    let x = error::field::<u32>(x, "an x coordinate")?;
+                    let y = error::field::<u32>(y, "a y coordinate")?;
+                    if x > MAX_COORDINATE || y > MAX_COORDINATE {
+                        return Err(ParseError::new(l, "coordinates no bigger than 2000"));
+                    }
+                    Ok((x, y))
+                }
+                _ => Err(ParseError::new(l, "a coordinate like 1, 6")),
+            }
+        })?;
+        if points.is_empty() {
+            return Err(ParseError::new("", "at least one coordinate").at_line(1));
+        }
+        Ok(Vec::from_iter(points.into_iter().enumerate().map(
+            |(idx, (x, y))| {
+                // let id=0 indicate no closest coordinate
+                let id = (idx + 1) as u32;
+                Coordinate { id, x, y }
+            },
+        )))
+    }
+
+    fn part1(coords: &Vec<Coordinate>) -> Answer {
+        let mut board = board(coords.as_slice());
+        for (point, cell) in board.iter_mut() {
+            *cell = nearest(point, coords.as_slice());
+        }
+        let infinite: HashSet<u32> = ids_on_edge(&board);
+        let cnts = counts(&board, &infinite);
+        // cells tied between coordinates have id 0 and aren't anyone's area
+        let largest = cnts
+            .iter()
+            .filter(|(id, _)| **id != 0)
+            .map(|(_, n)| n)
+            .max();
+        match largest {
+            Some(&largest) => largest.into(),
+            // every area reaches the edge, like with a single coordinate
+            None => Answer::Unsolved("no finite area".into()),
+        }
+    }
+
+    fn part2(coords: &Vec<Coordinate>) -> Answer {
+        safe_region(coords.as_slice(), 10000).into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let size = size.max(5);
+        // about as dense as the real inputs, 50 points across a few hundred
+        let extent = (((size as f64).sqrt() * 50.0) as i64).min(i64::from(MAX_COORDINATE));
+        // a point with one more on each side of it always has a finite area
+        let (c, r) = (extent / 2, extent / 4);
+        let mut points: Vec<(i64, i64)> =
+            vec![(c, c), (c - r, c), (c + r, c), (c, c - r), (c, c + r)];
+        while points.len() < size {
+            let point = (rng.range(0..=extent), rng.range(0..=extent));
+            if !points.contains(&point) {
+                points.push(point);
+            }
+        }
+        rng.shuffle(&mut points);
+        let lines: Vec<String> = points
+            .iter()
+            .map(|(x, y)| format!("{}, {}", x, y))
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_no_finite_area() {
+        let coords = Day06::parse("0, 0").unwrap();
+        assert_eq!(
+            Day06::part1(&coords),
+            Answer::Unsolved("no finite area".into())
+        );
+        let coords = Day06::parse("1, 1\n5, 1\n3, 9").unwrap();
+        assert_eq!(
+            Day06::part1(&coords),
+            Answer::Unsolved("no finite area".into())
+        );
+    }
+
+    const EXAMPLE: &str = "1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9";
+
+    #[test]
+    fn example_largest_area() {
+        let coords = Day06::parse(EXAMPLE).unwrap();
+        assert_eq!(Day06::part1(&coords), Answer::Int(17));
+    }
+
+    #[test]
+    fn example_safe_region() {
+        let coords = Day06::parse(EXAMPLE).unwrap();
+        assert_eq!(safe_region(&coords, 32), 16);
+    }
+
+    #[test]
+    fn ties_belong_to_nobody() {
+        let coords = Day06::parse(EXAMPLE).unwrap();
+        // (5, 0) is 5 from both 1, 1 and 5, 5
+        assert_eq!(nearest((5, 0), &coords), 0);
+        assert_eq!(nearest((3, 4), &coords), 4);
+    }
+
+    #[test]
+    fn generated_input_has_a_finite_area() {
+        let input = Day06::generate(&mut Rng::new(1), 20).unwrap();
+        let coords = Day06::parse(&input).unwrap();
+        assert_eq!(coords.len(), 20);
+        Day06::part1(&coords);
+    }
+
+    /// Reference for part 1: count every area on the board with a margin of one and of two
+    /// around it. Areas that grew with the margin are the infinite ones.
+    fn naive_largest_area(coords: &[Coordinate]) -> u32 {
+        let max_x = coords.iter().map(|c| c.x).max().unwrap() as i64;
+        let max_y = coords.iter().map(|c| c.y).max().unwrap() as i64;
+        let areas = |margin: i64| {
+            let mut areas: HashMap<u32, u32> = HashMap::new();
+            for x in -margin..=max_x + margin {
+                for y in -margin..=max_y + margin {
+                    let id = nearest((x, y), coords);
+                    if id != 0 {
+                        *areas.entry(id).or_insert(0) += 1;
+                    }
+                }
+            }
+            areas
+        };
+        let (near, far) = (areas(1), areas(2));
+        near.iter()
+            .filter(|(id, n)| far.get(id) == Some(n))
+            .map(|(_, n)| *n)
+            .max()
+            .unwrap()
+    }
+
+    /// Reference for part 2: check every cell within `limit` of the board, which is as far as a
+    /// safe cell could possibly be.
+    fn naive_safe_region(coords: &[Coordinate], limit: u32) -> u32 {
+        let max_x = coords.iter().map(|c| c.x).max().unwrap() as i64;
+        let max_y = coords.iter().map(|c| c.y).max().unwrap() as i64;
+        let reach = i64::from(limit);
+        let mut safe = 0;
+        for x in -reach..=max_x + reach {
+            for y in -reach..=max_y + reach {
+                if total((x, y), coords) < u64::from(limit) {
+                    safe += 1;
+                }
+            }
+        }
+        safe
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..8 {
+            let mut rng = Rng::new(seed);
+            let size = 5 + rng.index(6);
+            let coords = Day06::parse(&Day06::generate(&mut rng, size).unwrap()).unwrap();
+            let largest = naive_largest_area(&coords);
+            assert_eq!(
+                Day06::part1(&coords),
+                Answer::from(largest),
+                "seed {}",
+                seed
+            );
+            let limit = rng.range(1..=40) as u32 * size as u32;
+            let safe = naive_safe_region(&coords, limit);
+            assert_eq!(safe_region(&coords, limit), safe, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn safe_region_reaches_past_the_board() {
+        // the board is only 2 by 1, most of the safe cells are off it
+        let coords = Day06::parse("0, 0\n1, 0").unwrap();
+        assert_eq!(safe_region(&coords, 10), naive_safe_region(&coords, 10));
+        assert!(safe_region(&coords, 10) > 2);
+    }
+
+    #[test]
+    fn rejects_bad_coordinates() {
+        for input in ["", "1 1", "1,", "1, -1", "1, 2001"].iter() {
+            assert!(Day06::parse(input).is_err(), "{:?}", input);
+        }
+    }
+}
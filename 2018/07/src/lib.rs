@@ -0,0 +1,344 @@
+//! Day 7: The Sum of Its Parts.
+//!
+//! ```
+//! use advent_2018_07::{assembly_time, instructions, Day07};
+//! use advent_common::Solution;
+//!
+//! let directions = Day07::parse(
+//!     "Step C must be finished before step A can begin.
+//! Step C must be finished before step F can begin.
+//! Step A must be finished before step B can begin.
+//! Step A must be finished before step D can begin.
+//! Step B must be finished before step E can begin.
+//! Step D must be finished before step E can begin.
+//! Step F must be finished before step E can begin.",
+//! )
+//! .unwrap();
+//! assert_eq!(instructions(&directions), "CABDFE");
+//! assert_eq!(assembly_time(&directions, 2, 0), 15);
+//! ```
+
+use advent_common::{input, Answer, FromLine, ParseError, Rng, Solution};
+use std::collections::{BTreeSet, HashSet};
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, FromLine)]
+#[line("Step {step} must be finished before step {before} can begin.")]
+pub struct Direction {
+    pub step: char,
+    pub before: char,
+}
+
+/// Every step named in the directions, whether it comes before or after something.
+pub fn all_steps(directions: &[Direction]) -> BTreeSet<char> {
+    directions
+        .iter()
+        .flat_map(|d| vec![d.step, d.before])
+        .collect()
+}
+
+/// The steps in `remaining` nothing is still waiting on, in alphabetical order.
+pub fn determine_ready_steps(remaining: &BTreeSet<char>, directions: &[Direction]) -> Vec<char> {
+    let blocked: HashSet<char> = directions.iter().map(|d| d.before).collect();
+    Vec::from_iter(remaining.iter().filter(|s| !blocked.contains(s)).cloned())
+}
+
+/// A step that can never become ready because the directions loop back on themselves.
+fn cycle(directions: &[Direction]) -> Option<char> {
+    let mut remaining = all_steps(directions);
+    let mut directions = directions.to_vec();
+    loop {
+        let ready = determine_ready_steps(&remaining, &directions);
+        if ready.is_empty() {
+            return remaining.iter().next().cloned();
+        }
+        for step in ready {
+            remaining.remove(&step);
+        }
+        directions.retain(|d| remaining.contains(&d.step));
+    }
+}
+
+/// The order the steps are done in, picking alphabetically between ready steps.
+pub fn instructions(directions: &[Direction]) -> String {
+    let mut remaining = all_steps(directions);
+    let mut directions = directions.to_vec();
+    let mut acc = String::new();
+    while !remaining.is_empty() {
+        let next: char = *determine_ready_steps(&remaining, &directions)
+            .first()
+            // parse rejects cycles, so some step is always ready
+            .expect("No step is ready");
+        acc.push(next);
+        remaining.remove(&next);
+        directions.retain(|d| d.step != next);
+    }
+    acc
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Worker {
+    on: char,
+    busy_until: usize,
+}
+
+/// Every step takes `base` seconds plus its position in the alphabet.
+pub fn step_cost(step: char, base: usize) -> usize {
+    base + ((step as u8) - b'A' + 1) as usize
+}
+
+/// How long `num_workers` take to finish every step when each one costs `base` extra seconds.
+///
+/// Panics if there are no workers.
+pub fn assembly_time(directions: &[Direction], num_workers: usize, base: usize) -> usize {
+    assert!(num_workers > 0, "Assembly needs at least one worker");
+    let mut remaining = all_steps(directions);
+    let mut directions = directions.to_vec();
+    let mut workers: Vec<Worker> = Vec::with_capacity(num_workers);
+    let mut curtime: usize = 0;
+    while !remaining.is_empty() {
+        // hand the ready steps to whoever is idle
+        let ready = determine_ready_steps(&remaining, &directions);
+        for step in ready.into_iter().take(num_workers - workers.len()) {
+            remaining.remove(&step);
+            workers.push(Worker {
+                on: step,
+                busy_until: curtime + step_cost(step, base),
+            });
+        }
+        // then skip ahead to the next step being finished
+        curtime = workers
+            .iter()
+            .map(|w| w.busy_until)
+            .min()
+            // parsing rejects cycles, so with a worker some step is always in progress
+            .expect("No step is in progress");
+        let finished: Vec<char> = workers
+            .iter()
+            .filter(|w| w.busy_until == curtime)
+            .map(|w| w.on)
+            .collect();
+        workers.retain(|w| w.busy_until > curtime);
+        directions.retain(|d| !finished.contains(&d.step));
+    }
+    workers
+        .iter()
+        .map(|w| w.busy_until)
+        .fold(curtime, usize::max)
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 7;
+    type Input = Vec<Direction>;
+
+    fn parse(input: &str) -> Result<Vec<Direction>, ParseError> {
+        let directions = input::parse_lines(input, |l| {
+            let direction = Direction::from_str(l)?;
+            if direction.step.is_ascii_uppercase() && direction.before.is_ascii_uppercase() {
+                Ok(direction)
+            } else {
+                Err(ParseError::new(l, "steps named by capital letters"))
+            }
+        })?;
+        if let Some(step) = cycle(&directions) {
+            return Err(ParseError::new(
+                &step.to_string(),
+                "directions without a cycle, but this step can never start",
+            ));
+        }
+        Ok(directions)
+    }
+
+    fn part1(directions: &Vec<Direction>) -> Answer {
+        instructions(directions.as_slice()).into()
+    }
+
+    fn part2(directions: &Vec<Direction>) -> Answer {
+        assembly_time(directions.as_slice(), 5, 60).into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let steps = (size + 1).clamp(2, 26);
+        // edges only ever go forwards through a shuffled order, so the graph is acyclic
+        let mut order: Vec<char> = ('A'..='Z').take(steps).collect();
+        rng.shuffle(&mut order);
+        let mut edges: BTreeSet<(usize, usize)> = BTreeSet::new();
+        for later in 1..steps {
+            // every step after the first depends on something, so they all appear
+            edges.insert((rng.index(later), later));
+        }
+        while edges.len() < size.min(steps * (steps - 1) / 2) {
+            let (a, b) = (rng.index(steps), rng.index(steps));
+            if a < b {
+                edges.insert((a, b));
+            }
+        }
+        let mut lines: Vec<String> = edges
+            .iter()
+            .map(|&(a, b)| {
+                format!(
+                    "Step {} must be finished before step {} can begin.",
+                    order[a], order[b]
+                )
+            })
+            .collect();
+        rng.shuffle(&mut lines);
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.";
+
+    #[test]
+    fn example_order() {
+        let directions = Day07::parse(EXAMPLE).unwrap();
+        assert_eq!(instructions(&directions), "CABDFE");
+    }
+
+    #[test]
+    fn example_parallel() {
+        let directions = Day07::parse(EXAMPLE).unwrap();
+        assert_eq!(assembly_time(&directions, 2, 0), 15);
+    }
+
+    #[test]
+    fn keeps_every_last_step() {
+        // B and C both only come after A, dropping A's directions mustn't lose either of them
+        let directions = Day07::parse(
+            "Step A must be finished before step B can begin.\n\
+             Step A must be finished before step C can begin.",
+        )
+        .unwrap();
+        assert_eq!(instructions(&directions), "ABC");
+        assert_eq!(assembly_time(&directions, 2, 0), 4);
+        assert_eq!(assembly_time(&directions, 1, 0), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn needs_a_worker() {
+        let directions = Day07::parse(EXAMPLE).unwrap();
+        assembly_time(&directions, 0, 0);
+    }
+
+    #[test]
+    fn step_costs() {
+        assert_eq!(step_cost('A', 60), 61);
+        assert_eq!(step_cost('Z', 60), 86);
+        assert_eq!(step_cost('C', 0), 3);
+    }
+
+    #[test]
+    fn generated_input_orders_every_step() {
+        let input = Day07::generate(&mut Rng::new(1), 60).unwrap();
+        let directions = Day07::parse(&input).unwrap();
+        assert_eq!(directions.len(), 60);
+        match Day07::part1(&directions) {
+            Answer::Text(order) => assert_eq!(order.len(), 26),
+            other => panic!("unexpected answer {:?}", other),
+        }
+    }
+
+    /// Every step named in the directions, alphabetically.
+    fn naive_steps(directions: &[Direction]) -> Vec<char> {
+        ('A'..='Z')
+            .filter(|s| directions.iter().any(|d| d.step == *s || d.before == *s))
+            .collect()
+    }
+
+    fn naive_available(directions: &[Direction], done: &[char], step: char) -> bool {
+        directions
+            .iter()
+            .all(|d| d.before != step || done.contains(&d.step))
+    }
+
+    /// Reference for part 1: go through the alphabet from the top every time, taking the first
+    /// step that isn't done and whose prerequisites all are.
+    fn naive_order(directions: &[Direction]) -> String {
+        let steps = naive_steps(directions);
+        let mut done: Vec<char> = Vec::new();
+        while done.len() < steps.len() {
+            let next = steps
+                .iter()
+                .find(|s| !done.contains(s) && naive_available(directions, &done, **s))
+                .unwrap();
+            done.push(*next);
+        }
+        done.into_iter().collect()
+    }
+
+    /// Reference for part 2: tick the clock one second at a time.
+    fn naive_time(directions: &[Direction], workers: usize, base: usize) -> usize {
+        let steps = naive_steps(directions);
+        let mut done: Vec<char> = Vec::new();
+        let mut busy: Vec<(char, usize)> = Vec::new();
+        let mut second = 0;
+        loop {
+            for &step in &steps {
+                let started = done.contains(&step) || busy.iter().any(|(s, _)| *s == step);
+                if busy.len() < workers && !started && naive_available(directions, &done, step) {
+                    busy.push((step, step_cost(step, base)));
+                }
+            }
+            if busy.is_empty() {
+                return second;
+            }
+            second += 1;
+            for job in busy.iter_mut() {
+                job.1 -= 1;
+            }
+            done.extend(busy.iter().filter(|(_, left)| *left == 0).map(|(s, _)| *s));
+            busy.retain(|(_, left)| *left > 0);
+        }
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..30 {
+            let mut rng = Rng::new(seed);
+            let size = 1 + rng.index(80);
+            let directions = Day07::parse(&Day07::generate(&mut rng, size).unwrap()).unwrap();
+            let order = naive_order(&directions);
+            assert_eq!(instructions(&directions), order, "seed {}", seed);
+            let (workers, base) = (1 + rng.index(5), rng.index(20));
+            let time = naive_time(&directions, workers, base);
+            assert_eq!(
+                assembly_time(&directions, workers, base),
+                time,
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_directions() {
+        let step =
+            |a: &str, b: &str| format!("Step {} must be finished before step {} can begin.", a, b);
+        for input in [
+            step("a", "B"),
+            step("A", "AB"),
+            step("A", "A"),
+            format!("{}\n{}\n{}", step("A", "B"), step("B", "C"), step("C", "A")),
+        ]
+        .iter()
+        {
+            assert!(Day07::parse(input).is_err(), "{:?}", input);
+        }
+    }
+}
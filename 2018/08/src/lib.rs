@@ -0,0 +1,287 @@
+//! Day 8: Memory Maneuver.
+//!
+//! ```
+//! use advent_2018_08::Day08;
+//! use advent_common::Solution;
+//!
+//! let tree = Day08::parse("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2").unwrap();
+//! assert_eq!(tree.children.len(), 2);
+//! assert_eq!(tree.total_metadata(), 138);
+//! assert_eq!(tree.value(), Some(66));
+//! ```
+
+use advent_common::{error, input, Answer, ParseError, Rng, Solution};
+
+#[derive(Debug)]
+pub struct Node {
+    pub children: Vec<Node>,
+    pub metadata: Vec<u8>,
+}
+
+impl Node {
+    /// Sum of this node's own metadata.
+    pub fn metadata_sum(&self) -> u64 {
+        self.metadata.iter().map(|m| u64::from(*m)).sum::<u64>()
+    }
+    /// Sum of the metadata of this node and everything under it.
+    pub fn total_metadata(&self) -> u64 {
+        let mut total: u64 = self.metadata_sum();
+        for n in &self.children {
+            total += n.total_metadata();
+        }
+        total
+    }
+    /// The value of the node, or `None` if it doesn't fit in a u64.
+    pub fn value(&self) -> Option<u64> {
+        if self.children.is_empty() {
+            return Some(self.metadata_sum());
+        }
+        // each child once, however many entries refer to it
+        let values = self
+            .children
+            .iter()
+            .map(Node::value)
+            .collect::<Option<Vec<u64>>>()?;
+        let mut total: u64 = 0;
+        for idx in &self.metadata {
+            let i: usize = *idx as usize;
+            if i > 0 && i < self.children.len() + 1 {
+                total = total.checked_add(values[i - 1])?;
+            }
+        }
+        Some(total)
+    }
+}
+
+/// Real trees are only a handful of levels deep. Every level costs a stack frame while building
+/// the tree, walking it and dropping it, so anything much deeper is refused instead of risking
+/// the stack.
+const MAX_DEPTH: usize = 1000;
+
+/// Split off the next `n` numbers, which `what` needs.
+fn take<'a>(numbers: &'a [u8], n: usize, what: &str) -> Result<(&'a [u8], &'a [u8]), ParseError> {
+    if numbers.len() < n {
+        let expected = format!("{} more numbers for {}", n - numbers.len(), what);
+        return Err(ParseError::new("the end of the input", expected));
+    }
+    Ok(numbers.split_at(n))
+}
+
+/// Read the node at the start of `numbers`, returning it and the numbers after it.
+fn build_node(numbers: &[u8], depth: usize) -> Result<(Node, &[u8]), ParseError> {
+    if depth > MAX_DEPTH {
+        let expected = format!("a tree at most {} levels deep", MAX_DEPTH);
+        return Err(ParseError::new("a deeper one", expected));
+    }
+    let (header, mut rest) = take(numbers, 2, "a node header")?;
+    let mut children: Vec<Node> = Vec::with_capacity(header[0] as usize);
+    for _ in 0..header[0] {
+        let (node, after) = build_node(rest, depth + 1)?;
+        children.push(node);
+        rest = after;
+    }
+    let (metadata, rest) = take(rest, header[1] as usize, "the metadata")?;
+    let node = Node {
+        children,
+        metadata: metadata.to_vec(),
+    };
+    Ok((node, rest))
+}
+
+/// Write out `node` and everything under it, header first and metadata last.
+fn serialize(node: usize, children: &[Vec<usize>], rng: &mut Rng, out: &mut Vec<String>) {
+    let num_metadata = rng.range(1..=3);
+    out.push(children[node].len().to_string());
+    out.push(num_metadata.to_string());
+    for &child in &children[node] {
+        serialize(child, children, rng, out);
+    }
+    for _ in 0..num_metadata {
+        out.push(rng.range(1..=9).to_string());
+    }
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 8;
+    type Input = Node;
+
+    fn parse(input: &str) -> Result<Node, ParseError> {
+        let numbers: Vec<u8> = input::parse_lines(input, |l| {
+            l.split_whitespace()
+                .map(|v| error::field(v, "a number between 0 and 255"))
+                .collect::<Result<Vec<u8>, _>>()
+        })?
+        .concat();
+        let (node, rest) = build_node(numbers.as_slice(), 0)?;
+        if !rest.is_empty() {
+            let text = format!("{} more numbers", rest.len());
+            return Err(ParseError::new(&text, "the end of the tree"));
+        }
+        Ok(node)
+    }
+
+    fn part1(node: &Node) -> Answer {
+        node.total_metadata().into()
+    }
+
+    fn part2(node: &Node) -> Answer {
+        match node.value() {
+            // past an i64 it's still an answer, written out as text
+            Some(value) => i128::from(value).into(),
+            None => Answer::Unsolved("the root's value doesn't fit in a u64".into()),
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let nodes = size.max(1);
+        // hang each node off a random earlier one, leaving room in the u8 child count
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); nodes];
+        for node in 1..nodes {
+            let parent = loop {
+                let parent = rng.index(node);
+                if children[parent].len() < u8::MAX as usize {
+                    break parent;
+                }
+            };
+            children[parent].push(node);
+        }
+        let mut out: Vec<String> = Vec::new();
+        serialize(0, &children, rng, &mut out);
+        Some(out.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
+
+    #[test]
+    fn example_tree() {
+        let numbers: Vec<u8> = EXAMPLE.split(' ').map(|n| n.parse().unwrap()).collect();
+        let (root, rest) = build_node(&numbers, 0).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(root.metadata, vec![1, 1, 2]);
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].metadata, vec![10, 11, 12]);
+        assert_eq!(root.children[1].children[0].metadata, vec![99]);
+    }
+
+    #[test]
+    fn example_metadata_sum() {
+        let root = Day08::parse(EXAMPLE).unwrap();
+        assert_eq!(root.total_metadata(), 138);
+    }
+
+    #[test]
+    fn example_value() {
+        let root = Day08::parse(EXAMPLE).unwrap();
+        assert_eq!(root.children[0].value(), Some(33));
+        assert_eq!(root.children[1].value(), Some(0));
+        assert_eq!(root.value(), Some(66));
+    }
+
+    #[test]
+    fn values_deep_chains() {
+        // every node counts its only child twice, doubling the leaf's 5 on each level
+        let chain = |levels: usize| "1 2 ".repeat(levels) + "0 1 5" + &" 1 1".repeat(levels);
+        let root = Day08::parse(&chain(61)).unwrap();
+        assert_eq!(Day08::part2(&root), Answer::from("11529215046068469760"));
+        let root = Day08::parse(&chain(62)).unwrap();
+        assert_eq!(root.value(), None);
+        let root = Day08::parse(&chain(MAX_DEPTH)).unwrap();
+        assert_eq!(
+            Day08::part2(&root),
+            Answer::Unsolved("the root's value doesn't fit in a u64".into())
+        );
+    }
+
+    #[test]
+    fn generated_input_round_trips() {
+        let input = Day08::generate(&mut Rng::new(1), 300).unwrap();
+        let numbers = input.split(' ').count();
+        let node = Day08::parse(&input).unwrap();
+        fn count(node: &Node) -> usize {
+            1 + node.children.iter().map(count).sum::<usize>()
+        }
+        assert_eq!(count(&node), 300);
+        // two header numbers per node, the rest is metadata
+        assert!(numbers > 600);
+    }
+
+    /// Reference for part 1: walk the numbers with a stack of nodes still waiting on children,
+    /// adding up metadata without building a tree.
+    fn naive_metadata_sum(numbers: &[u8]) -> u64 {
+        let mut stack: Vec<(usize, usize)> = vec![(numbers[0] as usize, numbers[1] as usize)];
+        let mut at = 2;
+        let mut total = 0;
+        while let Some((children, metadata)) = stack.pop() {
+            if children > 0 {
+                stack.push((children - 1, metadata));
+                stack.push((numbers[at] as usize, numbers[at + 1] as usize));
+                at += 2;
+            } else {
+                total += numbers[at..at + metadata]
+                    .iter()
+                    .map(|&m| u64::from(m))
+                    .sum::<u64>();
+                at += metadata;
+            }
+        }
+        total
+    }
+
+    /// Reference for part 2: the value of the node starting at `numbers[0]`, and how many numbers
+    /// it takes up.
+    fn naive_value(numbers: &[u8]) -> (u64, usize) {
+        let (children, metadata) = (numbers[0] as usize, numbers[1] as usize);
+        let mut at = 2;
+        let mut values: Vec<u64> = Vec::new();
+        for _ in 0..children {
+            let (value, len) = naive_value(&numbers[at..]);
+            values.push(value);
+            at += len;
+        }
+        let entries = &numbers[at..at + metadata];
+        let value = if children == 0 {
+            entries.iter().map(|&m| u64::from(m)).sum()
+        } else {
+            entries
+                .iter()
+                .filter_map(|&m| values.get((m as usize).wrapping_sub(1)))
+                .sum()
+        };
+        (value, at + metadata)
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..30 {
+            let mut rng = Rng::new(seed);
+            let size = 1 + rng.index(200);
+            let input = Day08::generate(&mut rng, size).unwrap();
+            let numbers: Vec<u8> = input.split(' ').map(|n| n.parse().unwrap()).collect();
+            let root = Day08::parse(&input).unwrap();
+            let sum = naive_metadata_sum(&numbers);
+            assert_eq!(Day08::part1(&root), Answer::from(sum), "seed {}", seed);
+            let (value, _) = naive_value(&numbers);
+            assert_eq!(Day08::part2(&root), Answer::from(value), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_trees() {
+        for input in ["", "1 1", "0 3 1 2", "0 1 1 7", "1 1 0 1 5"].iter() {
+            assert!(Day08::parse(input).is_err(), "{:?}", input);
+        }
+        // a single chain of nodes, far deeper than any real tree
+        let chain = "1 0 ".repeat(MAX_DEPTH * 2) + "0 0";
+        assert!(Day08::parse(&chain).is_err());
+        let chain = "1 0 ".repeat(MAX_DEPTH) + "0 0";
+        assert!(Day08::parse(&chain).is_ok());
+    }
+}
@@ -0,0 +1,202 @@
+//! Day 9: Marble Mania.
+//!
+//! ```
+//! use advent_2018_09::{play, Configuration};
+//!
+//! let game = Configuration {
+//!     num_players: 10,
+//!     last_marble: 1618,
+//! };
+//! assert_eq!(play(game), 8317);
+//! ```
+
+use advent_common::{input, Answer, FromLine, ParseError, Rng, Solution};
+
+#[derive(Debug, Clone, Copy, FromLine)]
+#[line("{num_players} players; last marble is worth {last_marble} points")]
+pub struct Configuration {
+    pub num_players: u16,
+    pub last_marble: u64,
+}
+
+/// Real games end around 70000 points, and part 2 plays a hundred times as many marbles. Far
+/// bigger games would only ask for more memory than there is.
+const MAX_MARBLE: u64 = 100_000;
+
+#[derive(Clone, Copy)]
+struct Marble {
+    value: u64,
+    next: usize,
+    prev: usize,
+}
+
+/// The winning score once every marble up to the last has been played.
+pub fn play(conf: Configuration) -> u64 {
+    let mut players: Vec<u64> = vec![0; conf.num_players as usize];
+    let mut circle: Vec<Marble> = vec![
+        Marble {
+            value: 0,
+            next: 0,
+            prev: 0
+        };
+        conf.last_marble as usize + 1
+    ];
+    let mut curidx: usize = 0;
+    let mut nextidx: usize = 1;
+
+    for marble in 0..=conf.last_marble {
+        if marble % 23 != 0 {
+            // move next
+            // 0 -> 0 first, next one we'll have initialized
+            curidx = circle[curidx].next;
+            // where current marble points
+            let next = circle[curidx].next;
+            // set new marble value
+            circle[nextidx].value = marble;
+            circle[nextidx].next = next;
+            circle[nextidx].prev = curidx;
+            // fix pointers to prev/next of new marble
+            // now curidx is prev, and next is still next
+            circle[curidx].next = nextidx;
+            circle[next].prev = nextidx;
+            curidx = nextidx;
+            nextidx += 1;
+        } else {
+            // score player
+            players[(marble % u64::from(conf.num_players)) as usize] += marble;
+            // move back 7 times...
+            for _ in 0..7 {
+                curidx = circle[curidx].prev;
+            }
+            // add vale
+            players[(marble % u64::from(conf.num_players)) as usize] += circle[curidx].value;
+            // now "delete" by linking the prev/next marbles to each other
+            let prev = circle[curidx].prev;
+            let next = circle[curidx].next;
+            circle[prev].next = next;
+            circle[next].prev = prev;
+            curidx = next;
+        }
+    }
+
+    *players.iter().max().unwrap()
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 9;
+    type Input = Configuration;
+
+    fn parse(input: &str) -> Result<Configuration, ParseError> {
+        let line = input::first_line(input);
+        let conf = line.parse::<Configuration>().map_err(|e| e.at_line(1))?;
+        if conf.num_players == 0 {
+            return Err(ParseError::new(line, "at least one player").at_line(1));
+        }
+        if conf.last_marble > MAX_MARBLE {
+            return Err(
+                ParseError::new(line, "a last marble worth at most 100000 points").at_line(1),
+            );
+        }
+        Ok(conf)
+    }
+
+    fn part1(conf: &Configuration) -> Answer {
+        play(*conf).into()
+    }
+
+    fn part2(conf: &Configuration) -> Answer {
+        play(Configuration {
+            num_players: conf.num_players,
+            last_marble: conf.last_marble * 100,
+        })
+        .into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(format!(
+            "{} players; last marble is worth {} points",
+            rng.range(5..=500),
+            (size as u64).clamp(1, MAX_MARBLE)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(num_players: u16, last_marble: u64) -> u64 {
+        play(Configuration {
+            num_players,
+            last_marble,
+        })
+    }
+
+    #[test]
+    fn example_scores() {
+        assert_eq!(high_score(9, 25), 32);
+        assert_eq!(high_score(10, 1618), 8317);
+        assert_eq!(high_score(13, 7999), 146373);
+        assert_eq!(high_score(17, 1104), 2764);
+        assert_eq!(high_score(21, 6111), 54718);
+        assert_eq!(high_score(30, 5807), 37305);
+    }
+
+    #[test]
+    fn parses_configuration() {
+        let conf = Day09::parse("10 players; last marble is worth 1618 points").unwrap();
+        assert_eq!(conf.num_players, 10);
+        assert_eq!(conf.last_marble, 1618);
+    }
+
+    #[test]
+    fn generated_input_plays() {
+        let input = Day09::generate(&mut Rng::new(1), 1000).unwrap();
+        let conf = Day09::parse(&input).unwrap();
+        assert_eq!(conf.last_marble, 1000);
+        let input = Day09::generate(&mut Rng::new(1), 1_000_000).unwrap();
+        assert_eq!(Day09::parse(&input).unwrap().last_marble, MAX_MARBLE);
+        assert!(play(conf) > 0);
+    }
+
+    /// Reference for `play`: keep the circle in a plain Vec and insert and remove in place.
+    fn naive_play(players: usize, last_marble: u64) -> u64 {
+        let mut circle: Vec<u64> = vec![0];
+        let mut current: usize = 0;
+        let mut scores: Vec<u64> = vec![0; players];
+        for marble in 1..=last_marble {
+            if marble % 23 == 0 {
+                current = (current + circle.len() - 7) % circle.len();
+                scores[(marble % players as u64) as usize] += marble + circle.remove(current);
+                current %= circle.len();
+            } else {
+                current = (current + 1) % circle.len() + 1;
+                circle.insert(current, marble);
+            }
+        }
+        scores.into_iter().max().unwrap()
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..40 {
+            let mut rng = Rng::new(seed);
+            let conf = Configuration {
+                num_players: rng.range(1..=30) as u16,
+                last_marble: rng.range(1..=2000) as u64,
+            };
+            let expected = naive_play(conf.num_players as usize, conf.last_marble);
+            assert_eq!(play(conf), expected, "{:?}", conf);
+        }
+    }
+
+    #[test]
+    fn rejects_unplayable_games() {
+        assert!(Day09::parse("0 players; last marble is worth 25 points").is_err());
+        assert!(Day09::parse("9 players; last marble is worth 100001 points").is_err());
+        assert!(Day09::parse("9 players; last marble is worth 100000 points").is_ok());
+    }
+}
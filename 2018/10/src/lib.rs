@@ -0,0 +1,333 @@
+//! Day 10: The Stars Align.
+//!
+//! ```
+//! use advent_2018_10::{align, render, Pixel};
+//!
+//! let pixels: Vec<Pixel> = ["position=< 0, 0> velocity=< 1, 0>", "position=< 4, 0> velocity=<-1, 0>"]
+//!     .iter()
+//!     .map(|l| l.parse().unwrap())
+//!     .collect();
+//! let (seconds, aligned) = align(&pixels);
+//! assert_eq!(seconds, 2);
+//! assert_eq!(render(&aligned), "#");
+//! ```
+
+use advent_common::{input, Answer, Bounds, FromLine, Grid, ParseError, Point, Rng, Solution};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, FromLine)]
+#[line("position=<{x},{y}> velocity=<{dx},{dy}>")]
+pub struct Pixel {
+    pub x: i32,
+    pub y: i32,
+    pub dx: i32,
+    pub dy: i32,
+}
+
+impl Pixel {
+    pub fn point(&self) -> Point {
+        (self.x as i64, self.y as i64)
+    }
+    /// Move one second forward.
+    pub fn step(&mut self) {
+        self.x += self.dx;
+        self.y += self.dy;
+    }
+    /// Move one second back.
+    pub fn step_back(&mut self) {
+        self.x -= self.dx;
+        self.y -= self.dy;
+    }
+}
+
+/// Real positions are within about 60000 of the origin and velocities within 5. Capping them
+/// keeps the points from taking more than a few hundred thousand seconds to line up, and every
+/// position they pass through in an i32.
+const MAX_POSITION: i32 = 100_000;
+const MAX_VELOCITY: i32 = 10;
+
+/// The largest box part 1 will draw, real messages are about 60 by 10.
+const MAX_PICTURE: u64 = 1 << 20;
+
+/// The smallest box around every point.
+pub fn bounds(pixels: &[Pixel]) -> Bounds {
+    Bounds::of(pixels.iter().map(Pixel::point)).expect("no pixels?")
+}
+
+pub fn area(pixels: &[Pixel]) -> u64 {
+    bounds(pixels).area()
+}
+
+/// Draw the points inside their bounding box, `#` for a point and `.` for empty sky.
+pub fn render(pixels: &[Pixel]) -> String {
+    let mut grid = Grid::with_bounds(bounds(pixels), false);
+    for p in pixels {
+        grid[p.point()] = true;
+    }
+    grid.render(|&lit| if lit { '#' } else { '.' })
+}
+
+/// Step the points until the bounding box stops shrinking, returning how many seconds that took
+/// and where the points ended up.
+pub fn align(pixels: &[Pixel]) -> (usize, Vec<Pixel>) {
+    let mut pixels = pixels.to_owned();
+    let mut prev_area: u64 = u64::MAX;
+    for i in 0.. {
+        let new_area = area(&pixels);
+        if new_area > prev_area {
+            pixels.iter_mut().for_each(|s| s.step_back());
+            // -1 since we have to step back.
+            return (i - 1, pixels);
+        } else {
+            prev_area = new_area;
+            pixels.iter_mut().for_each(|s| s.step());
+        }
+    }
+    unreachable!()
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const YEAR: u16 = 2018;
+    const DAY: u8 = 10;
+    type Input = Vec<Pixel>;
+
+    fn parse(input: &str) -> Result<Vec<Pixel>, ParseError> {
+        let pixels = input::parse_lines(input, |l| {
+            let pixel = Pixel::from_str(l)?;
+            let within = |n: i32, max: i32| (-max..=max).contains(&n);
+            if !within(pixel.x, MAX_POSITION) || !within(pixel.y, MAX_POSITION) {
+                return Err(ParseError::new(l, "a position within 100000 of the origin"));
+            }
+            if !within(pixel.dx, MAX_VELOCITY) || !within(pixel.dy, MAX_VELOCITY) {
+                return Err(ParseError::new(l, "a velocity no faster than 10"));
+            }
+            Ok(pixel)
+        })?;
+        // points all moving together never line up, the box around them stays the same forever
+        let first = pixels
+            .first()
+            .ok_or_else(|| ParseError::new("", "at least one point").at_line(1))?;
+        if pixels.iter().all(|p| (p.dx, p.dy) == (first.dx, first.dy)) {
+            return Err(ParseError::new(
+                input::first_line(input),
+                "points moving relative to each other",
+            ));
+        }
+        Ok(pixels)
+    }
+
+    fn part1(pixels: &Vec<Pixel>) -> Answer {
+        let (_, aligned) = align(pixels.as_slice());
+        let bounds = bounds(aligned.as_slice());
+        if bounds.area() > MAX_PICTURE {
+            return Answer::Unsolved(format!(
+                "the points never get closer than a {} by {} box",
+                bounds.width(),
+                bounds.height()
+            ));
+        }
+        Answer::Art(render(aligned.as_slice()))
+    }
+
+    fn part2(pixels: &Vec<Pixel>) -> Answer {
+        align(pixels.as_slice()).0.into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let size = size.max(2);
+        // wide enough to fit all the points, but not so wide they start out of range
+        let width = (size as i64 / 4).clamp(8, 40_000);
+        let seconds = rng.range(10000..=11000);
+        let lines: Vec<String> = (0..size)
+            .map(|idx| {
+                // two points heading apart make sure the box eventually grows again
+                let (dx, dy) = match idx {
+                    0 => (1, 1),
+                    1 => (-1, -1),
+                    _ => {
+                        let speed =
+                            |rng: &mut Rng| rng.range(1..=5) * if rng.chance(0.5) { 1 } else { -1 };
+                        (speed(rng), speed(rng))
+                    }
+                };
+                let (x, y) = (rng.range(0..=width - 1), rng.range(0..=9));
+                format!(
+                    "position=<{:>6}, {:>6}> velocity=<{:>2}, {:>2}>",
+                    x - dx * seconds,
+                    y - dy * seconds,
+                    dx,
+                    dy
+                )
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>
+position=< 6, 10> velocity=<-2, -1>
+position=< 2, -4> velocity=< 2,  2>
+position=<-6, 10> velocity=< 2, -2>
+position=< 1,  8> velocity=< 1, -1>
+position=< 1,  7> velocity=< 1,  0>
+position=<-3, 11> velocity=< 1, -2>
+position=< 7,  6> velocity=<-1, -1>
+position=<-2,  3> velocity=< 1,  0>
+position=<-4,  3> velocity=< 2,  0>
+position=<10, -3> velocity=<-1,  1>
+position=< 5, 11> velocity=< 1, -2>
+position=< 4,  7> velocity=< 0, -1>
+position=< 8, -2> velocity=< 0,  1>
+position=<15,  0> velocity=<-2,  0>
+position=< 1,  6> velocity=< 1,  0>
+position=< 8,  9> velocity=< 0, -1>
+position=< 3,  3> velocity=<-1,  1>
+position=< 0,  5> velocity=< 0, -1>
+position=<-2,  2> velocity=< 2,  0>
+position=< 5, -2> velocity=< 1,  2>
+position=< 1,  4> velocity=< 2,  1>
+position=<-2,  7> velocity=< 2, -2>
+position=< 3,  6> velocity=<-1, -1>
+position=< 5,  0> velocity=< 1,  0>
+position=<-6,  0> velocity=< 2,  0>
+position=< 5,  9> velocity=< 1, -2>
+position=<14,  7> velocity=<-2,  0>
+position=<-3,  6> velocity=< 2, -1>";
+
+    const HI: &str = "\
+#...#..###
+#...#...#.
+#...#...#.
+#####...#.
+#...#...#.
+#...#...#.
+#...#...#.
+#...#..###";
+
+    #[test]
+    fn example_message() {
+        let pixels = Day10::parse(EXAMPLE).unwrap();
+        assert_eq!(Day10::part1(&pixels), Answer::Art(HI.to_string()));
+    }
+
+    #[test]
+    fn example_seconds() {
+        let pixels = Day10::parse(EXAMPLE).unwrap();
+        assert_eq!(Day10::part2(&pixels), Answer::Int(3));
+    }
+
+    #[test]
+    fn generated_input_aligns() {
+        let input = Day10::generate(&mut Rng::new(1), 40).unwrap();
+        let pixels = Day10::parse(&input).unwrap();
+        let (seconds, aligned) = align(&pixels);
+        assert!((10000..=11000).contains(&seconds));
+        assert!(bounds(&aligned).height() <= 10);
+    }
+
+    fn naive_positions(pixels: &[Pixel], seconds: i64) -> Vec<(i64, i64)> {
+        pixels
+            .iter()
+            .map(|p| {
+                (
+                    p.x as i64 + p.dx as i64 * seconds,
+                    p.y as i64 + p.dy as i64 * seconds,
+                )
+            })
+            .collect()
+    }
+
+    /// Reference for part 2: try every second up to `limit` and keep the last one with the
+    /// smallest bounding box.
+    fn naive_seconds(pixels: &[Pixel], limit: i64) -> i64 {
+        let area = |t: i64| {
+            let points = naive_positions(pixels, t);
+            let xs = points.iter().map(|p| p.0);
+            let ys = points.iter().map(|p| p.1);
+            (xs.clone().max().unwrap() - xs.min().unwrap() + 1)
+                * (ys.clone().max().unwrap() - ys.min().unwrap() + 1)
+        };
+        (0..=limit).fold(0, |best, t| if area(t) <= area(best) { t } else { best })
+    }
+
+    /// Reference for part 1: check every cell of the bounding box against every point.
+    fn naive_render(points: &[(i64, i64)]) -> String {
+        let xs = points.iter().map(|p| p.0);
+        let ys = points.iter().map(|p| p.1);
+        let (min_x, max_x) = (xs.clone().min().unwrap(), xs.max().unwrap());
+        let (min_y, max_y) = (ys.clone().min().unwrap(), ys.max().unwrap());
+        let rows: Vec<String> = (min_y..=max_y)
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| if points.contains(&(x, y)) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        rows.join("\n")
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        for seed in 0..5 {
+            let mut rng = Rng::new(seed);
+            let size = 2 + rng.index(30);
+            let pixels = Day10::parse(&Day10::generate(&mut rng, size).unwrap()).unwrap();
+            let seconds = naive_seconds(&pixels, 11500);
+            let (aligned_at, aligned) = align(&pixels);
+            assert_eq!(aligned_at as i64, seconds, "seed {}", seed);
+            let picture = naive_render(&naive_positions(&pixels, seconds));
+            assert_eq!(render(&aligned), picture, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn rejects_points_that_never_align() {
+        assert!(Day10::parse("").is_err());
+        assert!(Day10::parse("position=< 1, 2> velocity=< 0, 0>").is_err());
+        assert!(Day10::parse(
+            "position=< 1, 2> velocity=< 1, 0>\nposition=< 5, 2> velocity=< 1, 0>"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_points_out_of_range() {
+        let far = "position=<-2000000000, -2000000000> velocity=<-1, -1>\n\
+                   position=< 2000000000,  2000000000> velocity=< 1,  1>";
+        assert_eq!(Day10::parse(far).unwrap_err().line, 1);
+        let fast = "position=< 0, 0> velocity=<11, 0>\nposition=< 0, 0> velocity=< 0, 0>";
+        assert!(Day10::parse(fast).is_err());
+    }
+
+    #[test]
+    fn aligns_points_from_the_edge() {
+        let pixels = Day10::parse(
+            "position=<-100000, 0> velocity=< 1, 0>\nposition=< 100000, 0> velocity=<-1, 0>",
+        )
+        .unwrap();
+        assert_eq!(Day10::part2(&pixels), Answer::Int(100000));
+        assert_eq!(Day10::part1(&pixels), Answer::Art("#".to_string()));
+    }
+
+    #[test]
+    fn wont_draw_a_huge_picture() {
+        let pixels = Day10::parse(
+            "position=<-100000, -100000> velocity=<-1, -1>\n\
+             position=< 100000,  100000> velocity=< 1,  1>",
+        )
+        .unwrap();
+        assert_eq!(
+            Day10::part1(&pixels),
+            Answer::Unsolved("the points never get closer than a 200001 by 200001 box".into())
+        );
+    }
+}
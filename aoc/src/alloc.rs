@@ -0,0 +1,150 @@
+//! Counting what each phase allocates, with the `count-allocs` feature.
+//!
+//! The counting allocator wraps the system one and keeps its tallies per thread, so days solved
+//! side by side with `--jobs` don't see each other's allocations. Without the feature nothing is
+//! counted and [`measure`] reports `None`.
+
+// without the feature the allocator is only exercised by its tests
+#![cfg_attr(not(feature = "count-allocs"), allow(dead_code))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// What a phase allocated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// Number of allocations, counting every reallocation as one more.
+    pub allocations: u64,
+    /// Bytes asked for over all the allocations.
+    pub bytes: u64,
+    /// Most bytes the phase had allocated and not yet freed at any one time.
+    pub peak: u64,
+}
+
+#[derive(Clone, Copy)]
+struct Counts {
+    allocations: u64,
+    bytes: u64,
+    // memory allocated before the phase started and freed during it takes this below zero
+    live: i64,
+    peak: i64,
+}
+
+impl Counts {
+    const ZERO: Counts = Counts {
+        allocations: 0,
+        bytes: 0,
+        live: 0,
+        peak: 0,
+    };
+
+    fn allocated(&mut self, size: usize) {
+        self.allocations += 1;
+        self.bytes += size as u64;
+        self.resized(size as i64);
+    }
+
+    fn resized(&mut self, by: i64) {
+        self.live += by;
+        self.peak = self.peak.max(self.live);
+    }
+}
+
+thread_local! {
+    // const and without a destructor, so using it from inside the allocator can't allocate
+    static COUNTS: Cell<Counts> = const { Cell::new(Counts::ZERO) };
+}
+
+fn record<F: FnOnce(&mut Counts)>(f: F) {
+    // a thread that is shutting down just goes uncounted
+    let _ = COUNTS.try_with(|cell| {
+        let mut counts = cell.get();
+        f(&mut counts);
+        cell.set(counts);
+    });
+}
+
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(|c| c.allocated(layout.size()));
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(|c| c.allocated(layout.size()));
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(|c| c.resized(-(layout.size() as i64)));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            record(|c| {
+                c.allocations += 1;
+                c.bytes += new_size as u64;
+                c.resized(new_size as i64 - layout.size() as i64);
+            });
+        }
+        new
+    }
+}
+
+/// Run `f` and report what it allocated on this thread, if allocations are being counted.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Option<Usage>) {
+    COUNTS.with(|c| c.set(Counts::ZERO));
+    let result = f();
+    let counts = COUNTS.with(Cell::get);
+    let usage = Usage {
+        allocations: counts.allocations,
+        bytes: counts.bytes,
+        peak: counts.peak.max(0) as u64,
+    };
+    (
+        result,
+        Some(usage).filter(|_| cfg!(feature = "count-allocs")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_allocations_and_peak() {
+        let small = Layout::from_size_align(16, 8).unwrap();
+        let ((), usage) = measure(|| unsafe {
+            let a = Counting.alloc(small);
+            let b = Counting.alloc_zeroed(small);
+            Counting.dealloc(a, small);
+            let b = Counting.realloc(b, small, 64);
+            Counting.dealloc(b, Layout::from_size_align(64, 8).unwrap());
+        });
+        let counts = COUNTS.with(Cell::get);
+        assert_eq!(
+            (counts.allocations, counts.bytes, counts.live, counts.peak),
+            (3, 96, 0, 64)
+        );
+        if cfg!(feature = "count-allocs") {
+            // the test harness may allocate too, but never less than this
+            assert!(usage.is_some_and(|u| u.allocations >= 3 && u.peak >= 64));
+        } else {
+            assert_eq!(usage, None);
+        }
+    }
+}
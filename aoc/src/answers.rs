@@ -0,0 +1,121 @@
+//! The recorded answers `aoc verify` checks solutions against.
+//!
+//! Answers live in a TOML file keyed by year, then zero padded day, then part:
+//!
+//! ```toml
+//! [2018.01]
+//! part1 = 3
+//! part2 = 2
+//!
+//! [2018.07]
+//! part1 = "CABDFE"
+//! ```
+
+use advent_common::input::INPUTS_DIR;
+use advent_common::{Answer, Part};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Personal answers go with the personal inputs.
+pub fn default_path() -> PathBuf {
+    Path::new(INPUTS_DIR).join("answers.toml")
+}
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    table: Table,
+}
+
+impl Answers {
+    /// Load the answers file, treating a missing file as having no answers yet.
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Answers::default()),
+            Err(e) => return Err(format!("Couldn't read {}: {}", path.display(), e)),
+        };
+        let table = contents
+            .parse::<Table>()
+            .map_err(|e| format!("Couldn't parse {}: {}", path.display(), e))?;
+        Ok(Answers { table })
+    }
+
+    fn day(&self, year: u16, day: u8) -> Option<&Table> {
+        let days = self.table.get(&year.to_string())?.as_table()?;
+        days.get(&format!("{:02}", day))
+            .or_else(|| days.get(&day.to_string()))?
+            .as_table()
+    }
+
+    /// Record the answer for a part, replacing any there was.
+    pub fn record(&mut self, year: u16, day: u8, part: Part, answer: &Answer) {
+        let value = match answer {
+            Answer::Int(n) => Value::Integer(*n),
+            Answer::Text(s) | Answer::Art(s) => Value::String(s.clone()),
+            // nothing to check against later
+            Answer::Unsolved(_) => return,
+        };
+        let days = self
+            .table
+            .entry(year.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !days.is_table() {
+            *days = Value::Table(Table::new());
+        }
+        let days = days.as_table_mut().expect("just made sure it's a table");
+        // keep using whichever way the file already spells the day
+        let key = if days.contains_key(&day.to_string()) {
+            day.to_string()
+        } else {
+            format!("{:02}", day)
+        };
+        let parts = days
+            .entry(key)
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !parts.is_table() {
+            *parts = Value::Table(Table::new());
+        }
+        parts
+            .as_table_mut()
+            .expect("just made sure it's a table")
+            .insert(format!("part{}", part), value);
+    }
+
+    /// Write the answers back to `path`. Comments and formatting in the file aren't kept.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+        }
+        fs::write(path, self.table.to_string())
+            .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+    }
+
+    /// The recorded answer for a part, if there is one.
+    pub fn get(&self, year: u16, day: u8, part: Part) -> Option<Answer> {
+        let value = self.day(year, day)?.get(&format!("part{}", part))?;
+        match value {
+            Value::Integer(n) => Some(Answer::Int(*n)),
+            Value::String(s) if s.trim_end().contains('\n') => {
+                Some(Answer::Art(s.trim_end().to_string()))
+            }
+            Value::String(s) => Some(Answer::Text(s.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Whether two answers say the same thing, ignoring how they happen to be typed and any
+/// trailing whitespace on the lines of a picture.
+pub fn matches(expected: &Answer, actual: &Answer) -> bool {
+    let normalize = |a: &Answer| {
+        a.to_string()
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    normalize(expected) == normalize(actual)
+}
@@ -0,0 +1,155 @@
+//! Timing each phase of a day: parsing, part 1 and part 2.
+
+use crate::alloc::{self, Usage};
+use advent_common::{ParseError, Part, Puzzle};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Untimed runs before measuring, to warm caches and the allocator.
+    pub warmup: usize,
+    /// Timed runs per phase.
+    pub iterations: usize,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            warmup: 1,
+            iterations: 10,
+        }
+    }
+}
+
+/// Summary of the timed runs of a single phase.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    /// What one run allocated, when that's being counted.
+    pub memory: Option<Usage>,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>, memory: Option<Usage>) -> Stats {
+        assert!(!samples.is_empty(), "no samples to summarize");
+        samples.sort_unstable();
+        // nearest rank percentiles
+        let rank = |p: f64| {
+            let idx = (p * samples.len() as f64).ceil() as usize;
+            samples[idx.max(1) - 1]
+        };
+        Stats {
+            min: samples[0],
+            median: rank(0.5),
+            p95: rank(0.95),
+            memory,
+        }
+    }
+}
+
+pub struct Timings {
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+}
+
+fn time<T, F: FnMut() -> T>(options: &Options, mut f: F) -> Stats {
+    for _ in 0..options.warmup {
+        black_box(f());
+    }
+    // allocations are the same every run, so one untimed run is enough to count them
+    let (_, memory) = alloc::measure(|| black_box(f()));
+    let samples = (0..options.iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_samples(samples, memory)
+}
+
+/// Time parsing `raw` and solving each of `parts` from the parsed input.
+pub fn bench(
+    puzzle: &dyn Puzzle,
+    raw: &str,
+    parts: &[Part],
+    options: &Options,
+) -> Result<Timings, ParseError> {
+    // parse once up front so a bad input is reported instead of timed
+    let parsed = puzzle.parse(raw)?;
+    let parse = time(options, || puzzle.parse(raw));
+    let parts = parts
+        .iter()
+        .map(|&part| (part, time(options, || puzzle.solve(parsed.as_ref(), part))))
+        .collect();
+    Ok(Timings { parse, parts })
+}
+
+/// A byte count in the largest binary unit that keeps it at least 1.
+fn bytes(n: u64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB"];
+    let mut size = n as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < units.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", n)
+    } else {
+        format!("{:.1} {}", size, units[unit])
+    }
+}
+
+fn row(year: u16, day: u8, phase: &str, stats: &Stats) {
+    let memory = match stats.memory {
+        Some(usage) => format!(
+            " {:>10} {:>10} {:>10}",
+            usage.allocations,
+            bytes(usage.bytes),
+            bytes(usage.peak)
+        ),
+        None => String::new(),
+    };
+    println!(
+        "{:>4} {:>3}  {:<6} {:>12} {:>12} {:>12}{}",
+        year,
+        format!("{:02}", day),
+        phase,
+        format!("{:.2?}", stats.min),
+        format!("{:.2?}", stats.median),
+        format!("{:.2?}", stats.p95),
+        memory,
+    );
+}
+
+/// Print one row per day and phase, then the medians summed over everything.
+pub fn print_table(rows: &[(&dyn Puzzle, Timings)]) {
+    let memory = if cfg!(feature = "count-allocs") {
+        format!(" {:>10} {:>10} {:>10}", "allocs", "bytes", "peak")
+    } else {
+        String::new()
+    };
+    println!(
+        "{:>4} {:>3}  {:<6} {:>12} {:>12} {:>12}{}",
+        "year", "day", "phase", "min", "median", "p95", memory
+    );
+    let mut total = Duration::default();
+    for (puzzle, timings) in rows {
+        row(puzzle.year(), puzzle.day(), "parse", &timings.parse);
+        total += timings.parse.median;
+        for (part, stats) in &timings.parts {
+            row(
+                puzzle.year(),
+                puzzle.day(),
+                &format!("part {}", part),
+                stats,
+            );
+            total += stats.median;
+        }
+    }
+    println!("total of medians: {:.2?}", total);
+}
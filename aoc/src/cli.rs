@@ -0,0 +1,340 @@
+//! Command line parsing for the runner.
+
+use crate::{answers, bench};
+use advent_common::{Part, Puzzle};
+use std::path::PathBuf;
+use std::thread;
+
+pub const USAGE: &str = "\
+usage: aoc run [YEAR [DAY | FIRST-LAST]] [--part 1|2] [--input PATH] [--json] [--jobs N]
+       aoc bench [YEAR [DAY | FIRST-LAST]] [--part 1|2] [--input PATH]
+                 [--warmup N] [--iterations N]
+       aoc verify [YEAR [DAY | FIRST-LAST]] [--part 1|2] [--input PATH] [--answers PATH]
+       aoc generate YEAR DAY [--size N] [--seed N]
+       aoc new YEAR DAY
+       aoc fetch YEAR [DAY | FIRST-LAST]
+       aoc submit YEAR DAY --part 1|2 [--input PATH] [--answer ANSWER]
+       aoc stats [YEAR [DAY | FIRST-LAST]] [--file PATH | --url URL] [--csv]
+
+run solves every selected day, reading each one's input from inputs/<year>/<day>.txt.
+With no year every known day is run, with no day every day of that year.
+--input reads a single day's input from PATH instead, or from stdin if PATH is -.
+--json prints the answers and how long parsing and each part took as one JSON document.
+--jobs runs up to N days at once, 0 for one per CPU core. Answers are still printed in
+order, and a day that panics is reported as failed without stopping the others.
+
+bench times parsing and each part of the selected days separately, and prints the
+min, median and 95th percentile of the timed iterations (default 1 warmup, 10 timed).
+When built with --features count-allocs, bench and run --json also report how many
+allocations each phase made, the bytes they asked for and the peak bytes live at once.
+
+verify checks every selected part against the answers recorded in inputs/answers.toml,
+and reports each one as pass, FAIL or missing.
+
+generate prints random but valid input for a day to stdout, about N lines (or units, or
+nodes) large, default 1000. The same seed always gives the same input, default 1. Pipe it
+into another command with --input - to see how a day copes with bigger inputs.
+
+new creates YEAR/DAY with a skeleton solution and registers it with the runner. Run it
+from the root of the workspace.
+
+fetch downloads each selected day's input into inputs/<year>/<day>.txt, every day of the
+year if none is given. Inputs already there are left alone. It needs the session cookie of
+a logged in browser in AOC_SESSION or as session in inputs/config.toml, which can also set
+base_url to fetch from somewhere else (AOC_BASE_URL overrides it).
+
+submit solves a part and sends the answer to the site, or sends ANSWER instead. Right
+answers are recorded in inputs/answers.toml, wrong ones in inputs/guesses.toml so they are
+never sent twice, along with any wait the site asks for.
+
+stats ranks the members of a private leaderboard on each selected day by when they got
+both stars, with how long after the puzzle unlocked they got each one and the time between
+them. It reads the leaderboard's JSON export from PATH, or downloads it from URL or from
+the leaderboard set in inputs/config.toml (an id or a URL) for YEAR, reusing a download
+for 15 minutes as the site asks. --csv prints every star's Unix time and the times in
+seconds as CSV instead.";
+
+pub enum Command {
+    Run(Selection, Run),
+    Bench(Selection, bench::Options),
+    Verify(Selection, PathBuf),
+    Generate(Selection, Generate),
+    New(u16, u8),
+    /// The year, and the range of days.
+    Fetch(u16, (u8, u8)),
+    /// The day and part, and the answer to send instead of solving it.
+    Submit(Selection, Option<String>),
+    Stats(Selection, Stats),
+    Help,
+}
+
+/// Options for `aoc run`.
+#[derive(Debug, Clone, Copy)]
+pub struct Run {
+    /// Print JSON instead of text.
+    pub json: bool,
+    /// How many days to run at once.
+    pub jobs: usize,
+}
+
+impl Default for Run {
+    fn default() -> Run {
+        Run {
+            json: false,
+            jobs: 1,
+        }
+    }
+}
+
+/// Options for `aoc generate`.
+#[derive(Debug, Clone, Copy)]
+pub struct Generate {
+    pub size: usize,
+    pub seed: u64,
+}
+
+impl Default for Generate {
+    fn default() -> Generate {
+        Generate {
+            size: 1000,
+            seed: 1,
+        }
+    }
+}
+
+/// Options for `aoc stats`.
+#[derive(Debug, Default)]
+pub struct Stats {
+    /// Read the leaderboard from this file instead of downloading it.
+    pub file: Option<PathBuf>,
+    /// Download the leaderboard from here instead of the configured one.
+    pub url: Option<String>,
+    /// Print CSV instead of tables.
+    pub csv: bool,
+}
+
+/// Which days and parts a command applies to.
+#[derive(Debug, Default)]
+pub struct Selection {
+    pub year: Option<u16>,
+    pub days: Option<(u8, u8)>,
+    pub part: Option<Part>,
+    pub input: Option<String>,
+}
+
+impl Selection {
+    pub fn matches(&self, puzzle: &dyn Puzzle) -> bool {
+        let year_ok = self.year.is_none_or(|y| y == puzzle.year());
+        let day_ok = self
+            .days
+            .is_none_or(|(first, last)| (first..=last).contains(&puzzle.day()));
+        year_ok && day_ok
+    }
+
+    pub fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(p) => vec![p],
+            None => Part::BOTH.to_vec(),
+        }
+    }
+}
+
+fn parse_days(arg: &str) -> Result<(u8, u8), String> {
+    let day = |s: &str| {
+        s.parse::<u8>()
+            .ok()
+            .filter(|d| (1..=25).contains(d))
+            .ok_or_else(|| format!("'{}' is not a day between 1 and 25", s))
+    };
+    let mut split = arg.splitn(2, '-');
+    let first = day(split.next().unwrap_or(""))?;
+    let last = match split.next() {
+        Some(s) => day(s)?,
+        None => first,
+    };
+    if first > last {
+        return Err(format!("day range {} is backwards", arg));
+    }
+    Ok((first, last))
+}
+
+fn parse_part(arg: Option<String>) -> Result<Part, String> {
+    match arg.as_deref() {
+        Some("1") => Ok(Part::One),
+        Some("2") => Ok(Part::Two),
+        Some(other) => Err(format!("'{}' is not a part, expected 1 or 2", other)),
+        None => Err("--part needs a value".to_string()),
+    }
+}
+
+fn parse_count(flag: &str, arg: Option<String>) -> Result<usize, String> {
+    let arg = arg.ok_or_else(|| format!("{} needs a value", flag))?;
+    arg.parse()
+        .map_err(|_| format!("'{}' is not a valid count for {}", arg, flag))
+}
+
+/// Parse the day selection shared by every command. Options the selection doesn't know about are
+/// offered to `extra`, which returns whether it used them.
+fn parse_selection<I, F>(mut args: I, mut extra: F) -> Result<Selection, String>
+where
+    I: Iterator<Item = String>,
+    F: FnMut(&str, &mut I) -> Result<bool, String>,
+{
+    let mut selection = Selection::default();
+    let mut positional: Vec<String> = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => selection.part = Some(parse_part(args.next())?),
+            "--input" | "-i" => {
+                let path = args.next().ok_or("--input needs a path")?;
+                selection.input = Some(path);
+            }
+            _ if extra(&arg, &mut args)? => {}
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+            _ => positional.push(arg),
+        }
+    }
+    let mut positional = positional.into_iter();
+    if let Some(year) = positional.next() {
+        let year = year
+            .parse()
+            .map_err(|_| format!("'{}' is not a year", year))?;
+        selection.year = Some(year);
+    }
+    if let Some(days) = positional.next() {
+        selection.days = Some(parse_days(&days)?);
+    }
+    if let Some(extra) = positional.next() {
+        return Err(format!("unexpected argument {}", extra));
+    }
+    Ok(selection)
+}
+
+pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    match args.next().as_deref() {
+        Some("run") => {
+            let mut options = Run::default();
+            let selection = parse_selection(args, |flag, args| {
+                match flag {
+                    "--json" => options.json = true,
+                    "--jobs" | "-j" => {
+                        options.jobs = match parse_count(flag, args.next())? {
+                            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+                            n => n,
+                        }
+                    }
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            Ok(Command::Run(selection, options))
+        }
+        Some("bench") => {
+            let mut options = bench::Options::default();
+            let selection = parse_selection(args, |flag, args| {
+                match flag {
+                    "--warmup" => options.warmup = parse_count(flag, args.next())?,
+                    "--iterations" | "-n" => options.iterations = parse_count(flag, args.next())?,
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            if options.iterations == 0 {
+                return Err("--iterations must be at least 1".to_string());
+            }
+            Ok(Command::Bench(selection, options))
+        }
+        Some("verify") => {
+            let mut answers = answers::default_path();
+            let selection = parse_selection(args, |flag, args| {
+                if flag != "--answers" {
+                    return Ok(false);
+                }
+                answers = args.next().ok_or("--answers needs a path")?.into();
+                Ok(true)
+            })?;
+            Ok(Command::Verify(selection, answers))
+        }
+        Some("generate") => {
+            let mut options = Generate::default();
+            let selection = parse_selection(args, |flag, args| {
+                match flag {
+                    "--size" | "-s" => options.size = parse_count(flag, args.next())?,
+                    "--seed" => options.seed = parse_count(flag, args.next())? as u64,
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            if selection.days.is_none_or(|(first, last)| first != last) {
+                return Err("generate needs a single year and day".to_string());
+            }
+            Ok(Command::Generate(selection, options))
+        }
+        Some("new") => {
+            let selection = parse_selection(args, |_, _| Ok(false))?;
+            match (
+                selection.year,
+                selection.days,
+                selection.part,
+                selection.input,
+            ) {
+                (Some(year), Some((day, last)), None, None) if day == last => {
+                    Ok(Command::New(year, day))
+                }
+                _ => Err("new needs just a year and a day".to_string()),
+            }
+        }
+        Some("fetch") => {
+            let selection = parse_selection(args, |_, _| Ok(false))?;
+            match (selection.year, selection.part, selection.input) {
+                (Some(year), None, None) => {
+                    Ok(Command::Fetch(year, selection.days.unwrap_or((1, 25))))
+                }
+                _ => Err("fetch needs a year, and optionally the days".to_string()),
+            }
+        }
+        Some("submit") => {
+            let mut answer = None;
+            let selection = parse_selection(args, |flag, args| {
+                if flag != "--answer" {
+                    return Ok(false);
+                }
+                answer = Some(args.next().ok_or("--answer needs a value")?);
+                Ok(true)
+            })?;
+            if selection.days.is_none_or(|(first, last)| first != last) {
+                return Err("submit needs a single year and day".to_string());
+            }
+            if selection.part.is_none() {
+                return Err("submit needs a --part".to_string());
+            }
+            Ok(Command::Submit(selection, answer))
+        }
+        Some("stats") => {
+            let mut options = Stats::default();
+            let selection = parse_selection(args, |flag, args| {
+                match flag {
+                    "--file" | "-f" => {
+                        options.file = Some(args.next().ok_or("--file needs a path")?.into())
+                    }
+                    "--url" => options.url = Some(args.next().ok_or("--url needs a URL")?),
+                    "--csv" => options.csv = true,
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            })?;
+            if selection.part.is_some() || selection.input.is_some() {
+                return Err("stats takes --file, not --part or --input".to_string());
+            }
+            if options.file.is_some() && options.url.is_some() {
+                return Err("stats reads --file or --url, not both".to_string());
+            }
+            if options.file.is_none() && selection.year.is_none() {
+                return Err("stats needs a year to download the leaderboard".to_string());
+            }
+            Ok(Command::Stats(selection, options))
+        }
+        Some("help") | Some("--help") | Some("-h") | None => Ok(Command::Help),
+        Some(other) => Err(format!("unknown command {}", other)),
+    }
+}
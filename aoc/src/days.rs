@@ -0,0 +1,63 @@
+//! Every solved day, in year/day order.
+
+use advent_common::Puzzle;
+
+pub static DAYS: &[&dyn Puzzle] = &[
+    &advent_2018_01::Day01,
+    &advent_2018_02::Day02,
+    &advent_2018_03::Day03,
+    &advent_2018_04::Day04,
+    &advent_2018_05::Day05,
+    &advent_2018_06::Day06,
+    &advent_2018_07::Day07,
+    &advent_2018_08::Day08,
+    &advent_2018_09::Day09,
+    &advent_2018_10::Day10,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_common::Rng;
+
+    /// Damage a generated input the way a bad copy and paste or a hostile file might.
+    fn mutate(rng: &mut Rng, input: &str) -> String {
+        let mut bytes = input.as_bytes().to_vec();
+        for _ in 0..=rng.below(4) {
+            let at = rng.index(bytes.len() + 1);
+            match rng.below(5) {
+                0 => bytes.truncate(at),
+                1 if at < bytes.len() => bytes[at] ^= 1 << rng.below(8),
+                2 => bytes.insert(at, *rng.choose(b"0123456789-, \n#@<>xyz")),
+                3 => {
+                    let huge = rng.choose(&["99999999999999999999", "4294967296", "-1", "0"]);
+                    bytes.splice(at..at, huge.bytes());
+                }
+                _ => {
+                    let end = bytes[at..]
+                        .iter()
+                        .position(|&b| b == b'\n')
+                        .map_or(bytes.len(), |n| at + n);
+                    bytes.drain(at..end);
+                }
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    #[test]
+    fn parsers_survive_damaged_input() {
+        for puzzle in DAYS {
+            for seed in 0..200 {
+                let mut rng = Rng::new(seed);
+                // days fresh from `aoc new` don't have a generator yet
+                let input = match puzzle.generate(seed, 10) {
+                    Some(input) => input,
+                    None => break,
+                };
+                // an error is fine, a panic is not
+                let _ = puzzle.parse(&mutate(&mut rng, &input));
+            }
+        }
+    }
+}
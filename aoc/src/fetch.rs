@@ -0,0 +1,257 @@
+//! Downloading personal puzzle inputs into `inputs/<year>/<day>.txt`.
+//!
+//! The session token is the value of the `session` cookie of a logged in browser. It's read from
+//! `AOC_SESSION`, or from `session` in `inputs/config.toml`:
+//!
+//! ```toml
+//! session = "53616c7465645f5f..."
+//! # where to fetch from, for testing against something other than the real site
+//! base_url = "http://localhost:8080"
+//! # the id of a private leaderboard for `aoc stats`, or the full URL of its JSON export
+//! leaderboard = "123456"
+//! ```
+//!
+//! `AOC_BASE_URL` overrides the configured base URL. Inputs already on disk are never fetched
+//! again, the site asks that they be cached.
+
+use advent_common::input::INPUTS_DIR;
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use toml::Table;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Who is asking, as the site requests of automated tools.
+const USER_AGENT: &str = concat!(
+    "aoc/",
+    env!("CARGO_PKG_VERSION"),
+    " (github.com/aeroevan/aoc by aeroevan@gmail.com)"
+);
+
+/// Personal settings go with the personal inputs.
+pub fn default_config_path() -> PathBuf {
+    Path::new(INPUTS_DIR).join("config.toml")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    pub leaderboard: Option<String>,
+}
+
+impl Config {
+    /// Settings from the config file at `path`, if there is one, overridden by the environment.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let mut config = match fs::read_to_string(path) {
+            Ok(contents) => Config::parse(&contents)
+                .map_err(|e| format!("Couldn't parse {}: {}", path.display(), e))?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Config::parse("")?,
+            Err(e) => return Err(format!("Couldn't read {}: {}", path.display(), e)),
+        };
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+        Ok(config)
+    }
+
+    fn parse(contents: &str) -> Result<Config, String> {
+        let table = contents.parse::<Table>().map_err(|e| e.to_string())?;
+        let text = |key: &str| match table.get(key) {
+            None => Ok(None),
+            Some(value) => value
+                .as_str()
+                .map(|s| Some(s.to_string()))
+                .ok_or_else(|| format!("{} should be a string", key)),
+        };
+        Ok(Config {
+            session: text("session")?,
+            base_url: text("base_url")?.unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            leaderboard: text("leaderboard")?,
+        })
+    }
+
+    /// The `Cookie` header that logs in with the session token.
+    pub fn cookie(&self) -> Result<String, String> {
+        let session = self.session.as_deref().ok_or(
+            "No session token, set AOC_SESSION or session in inputs/config.toml to the session \
+             cookie of a logged in browser",
+        )?;
+        Ok(format!("session={}", session.trim()))
+    }
+
+    /// The URL of `page` of a day, like `input` or `answer`.
+    pub fn day_url(&self, year: u16, day: u8, page: &str) -> String {
+        format!(
+            "{}/{}/day/{}/{}",
+            self.base_url.trim_end_matches('/'),
+            year,
+            day,
+            page
+        )
+    }
+
+    /// The URL of the JSON export of the configured private leaderboard for `year`.
+    pub fn leaderboard_url(&self, year: u16) -> Result<String, String> {
+        let leaderboard = self
+            .leaderboard
+            .as_deref()
+            .ok_or("No leaderboard, set leaderboard in inputs/config.toml to its id or URL")?;
+        if leaderboard.contains("://") {
+            return Ok(leaderboard.to_string());
+        }
+        Ok(format!(
+            "{}/{}/leaderboard/private/view/{}.json",
+            self.base_url.trim_end_matches('/'),
+            year,
+            leaderboard.trim()
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already on disk.
+    Cached,
+    /// Downloaded, this many bytes.
+    Downloaded(usize),
+}
+
+/// An HTTP client that introduces itself properly.
+pub fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(30))
+        .build()
+}
+
+fn download(config: &Config, year: u16, day: u8) -> Result<String, String> {
+    let url = config.day_url(year, day, "input");
+    let response = match agent().get(&url).set("Cookie", &config.cookie()?).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, response)) => {
+            // the site explains what went wrong, like a day that isn't unlocked yet, in the body
+            let reason = response.into_string().unwrap_or_default();
+            let reason = reason.lines().next().unwrap_or("").trim();
+            return Err(format!("Couldn't fetch {}: HTTP {} {}", url, code, reason));
+        }
+        Err(e) => return Err(format!("Couldn't fetch {}: {}", url, e)),
+    };
+    let mut body = String::new();
+    response
+        .into_reader()
+        .read_to_string(&mut body)
+        .map_err(|e| format!("Couldn't read {}: {}", url, e))?;
+    if body.trim().is_empty() {
+        return Err(format!("{} returned an empty input", url));
+    }
+    Ok(body)
+}
+
+/// Make sure the input for `day` of `year` is at `path`, downloading it if it isn't yet.
+pub fn fetch(config: &Config, year: u16, day: u8, path: &Path) -> Result<Fetched, String> {
+    if path.exists() {
+        return Ok(Fetched::Cached);
+    }
+    let body = download(config, year, day)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    }
+    // write next to it first, so an interrupted write never leaves a partial input to be cached
+    let partial = path.with_extension("txt.part");
+    fs::write(&partial, &body)
+        .and_then(|_| fs::rename(&partial, path))
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+    Ok(Fetched::Downloaded(body.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub;
+
+    #[test]
+    fn downloads_once_then_uses_the_cache() {
+        let (base_url, server) = stub::server(1, "200 OK", "+1\n-2\n");
+        let config = Config {
+            session: Some("abc123\n".to_string()),
+            base_url: base_url + "/",
+            leaderboard: None,
+        };
+        let dir = stub::scratch("fetch-cache");
+        let path = dir.join("2018").join("01.txt");
+        assert_eq!(fetch(&config, 2018, 1, &path), Ok(Fetched::Downloaded(6)));
+        assert_eq!(fetch(&config, 2018, 1, &path), Ok(Fetched::Cached));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "+1\n-2\n");
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        let head = requests[0].to_lowercase();
+        assert!(
+            head.starts_with("get /2018/day/1/input http/1.1"),
+            "{}",
+            head
+        );
+        assert!(head.contains("cookie: session=abc123\r\n"), "{}", head);
+        assert!(head.contains("user-agent: aoc/"), "{}", head);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reports_the_site_refusing() {
+        let (base_url, server) = stub::server(
+            1,
+            "404 Not Found",
+            "Please don't repeatedly request this endpoint before it unlocks!\n",
+        );
+        let config = Config {
+            session: Some("abc123".to_string()),
+            base_url,
+            leaderboard: None,
+        };
+        let dir = stub::scratch("fetch-refused");
+        let path = dir.join("2018").join("25.txt");
+        let error = fetch(&config, 2018, 25, &path).unwrap_err();
+        assert!(error.contains("HTTP 404 Please don't"), "{}", error);
+        assert!(!path.exists());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn needs_a_session() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.base_url, DEFAULT_BASE_URL);
+        let path = stub::scratch("fetch-session").join("01.txt");
+        assert!(fetch(&config, 2018, 1, &path)
+            .unwrap_err()
+            .contains("AOC_SESSION"));
+    }
+
+    #[test]
+    fn reads_config() {
+        let config = Config::parse("session = \"abc\"\nbase_url = \"http://localhost:1\"").unwrap();
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(
+            config.day_url(2018, 7, "input"),
+            "http://localhost:1/2018/day/7/input"
+        );
+        assert!(Config::parse("session = 1").is_err());
+
+        let config = Config::parse("leaderboard = \"42\"").unwrap();
+        assert_eq!(
+            config.leaderboard_url(2018).unwrap(),
+            "https://adventofcode.com/2018/leaderboard/private/view/42.json"
+        );
+        let config = Config::parse("leaderboard = \"http://localhost:1/42.json\"").unwrap();
+        assert_eq!(
+            config.leaderboard_url(2018).unwrap(),
+            "http://localhost:1/42.json"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Machine readable output of `aoc run --json`.
+//!
+//! Everything is printed as a single document once every day has run:
+//!
+//! ```json
+//! {"results": [
+//!   {"year": 2018, "day": 1, "parse_ns": 2100,
+//!    "parts": [{"part": 1, "answer": {"type": "integer", "value": 3}, "elapsed_ns": 480}]},
+//!   {"year": 2018, "day": 2, "error": "Couldn't read inputs/2018/02.txt: ..."}
+//! ]}
+//! ```
+//!
+//! Answers are typed as `integer`, `string`, or `art` for the multiline pictures, whose value is
+//! the picture with its rows separated by newlines.
+//!
+//! Built with the `count-allocs` feature, the day gains a `parse_memory` and each part a `memory`
+//! object, like `{"allocations": 12, "bytes": 4096, "peak_bytes": 2048}`.
+
+use crate::alloc::Usage;
+use crate::Solved;
+use advent_common::{Answer, Puzzle};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+fn nanos(d: Duration) -> u64 {
+    d.as_nanos() as u64
+}
+
+fn memory(usage: &Usage) -> Value {
+    json!({
+        "allocations": usage.allocations,
+        "bytes": usage.bytes,
+        "peak_bytes": usage.peak,
+    })
+}
+
+pub fn answer(answer: &Answer) -> Value {
+    match answer {
+        Answer::Int(n) => json!({"type": "integer", "value": n}),
+        Answer::Text(s) => json!({"type": "string", "value": s}),
+        Answer::Art(s) => json!({"type": "art", "value": s}),
+        Answer::Unsolved(why) => json!({"type": "unsolved", "value": why}),
+    }
+}
+
+pub fn solved(puzzle: &dyn Puzzle, solved: &Solved) -> Value {
+    let parts: Vec<Value> = solved
+        .parts
+        .iter()
+        .map(|(part, ans, phase)| {
+            let mut value = json!({
+                "part": part.number(),
+                "answer": answer(ans),
+                "elapsed_ns": nanos(phase.elapsed),
+            });
+            if let Some(usage) = &phase.memory {
+                value["memory"] = memory(usage);
+            }
+            value
+        })
+        .collect();
+    let mut value = json!({
+        "year": puzzle.year(),
+        "day": puzzle.day(),
+        "parse_ns": nanos(solved.parse.elapsed),
+        "parts": parts,
+    });
+    if let Some(usage) = &solved.parse.memory {
+        value["parse_memory"] = memory(usage);
+    }
+    value
+}
+
+pub fn failed(puzzle: &dyn Puzzle, error: &str) -> Value {
+    json!({
+        "year": puzzle.year(),
+        "day": puzzle.day(),
+        "error": error,
+    })
+}
+
+pub fn document(results: Vec<Value>) -> Value {
+    json!({ "results": results })
+}
@@ -0,0 +1,406 @@
+//! Statistics from a private leaderboard's JSON export.
+//!
+//! The export is what the site serves at `/<year>/leaderboard/private/view/<id>.json`:
+//!
+//! ```json
+//! {"event": "2018", "members": {"42": {"id": 42, "name": "Evan", "completion_day_level": {
+//!   "1": {"1": {"get_star_ts": 1543641234}, "2": {"get_star_ts": 1543641800}}}}}}
+//! ```
+//!
+//! Older exports have the timestamps as strings, and members who haven't set a name have `null`.
+//! Times are reported from when the puzzle unlocked, midnight US Eastern time.
+
+use crate::fetch::{self, Config};
+use advent_common::input::INPUTS_DIR;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// The site asks for the leaderboard to be fetched at most this often.
+const REFRESH: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    /// When each star of each day was got, as Unix times.
+    pub stars: BTreeMap<u8, [Option<i64>; 2]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub year: u16,
+    pub members: Vec<Member>,
+}
+
+/// A whole number that might have been written as a string.
+fn number(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn member(key: &str, value: &Value) -> Result<Member, String> {
+    let id = value
+        .get("id")
+        .and_then(number)
+        .or_else(|| key.parse().ok())
+        .ok_or_else(|| format!("member {} has no id", key))?;
+    let id = u64::try_from(id).map_err(|_| format!("member {} has id {}", key, id))?;
+    let name = match value.get("name").and_then(Value::as_str) {
+        Some(name) => name.to_string(),
+        None => format!("(anonymous user #{})", id),
+    };
+    let mut stars = BTreeMap::new();
+    if let Some(days) = value.get("completion_day_level").and_then(Value::as_object) {
+        for (day, parts) in days {
+            let day: u8 = day
+                .parse()
+                .map_err(|_| format!("member {} has a star for day '{}'", id, day))?;
+            let mut times = [None, None];
+            for (idx, part) in ["1", "2"].iter().enumerate() {
+                times[idx] = parts
+                    .get(part)
+                    .and_then(|p| p.get("get_star_ts"))
+                    .and_then(number);
+            }
+            stars.insert(day, times);
+        }
+    }
+    Ok(Member { id, name, stars })
+}
+
+impl Leaderboard {
+    pub fn parse(json: &str) -> Result<Leaderboard, String> {
+        let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let year = value
+            .get("event")
+            .and_then(number)
+            .ok_or("there's no event year")?;
+        let year = u16::try_from(year).map_err(|_| format!("{} isn't a year", year))?;
+        let members = value
+            .get("members")
+            .and_then(Value::as_object)
+            .ok_or("there are no members")?;
+        let mut members = members
+            .iter()
+            .map(|(key, value)| member(key, value))
+            .collect::<Result<Vec<_>, _>>()?;
+        members.sort_by_key(|m| m.id);
+        Ok(Leaderboard { year, members })
+    }
+
+    /// Every day anyone has a star for.
+    pub fn days(&self) -> Vec<u8> {
+        let mut days: Vec<u8> = self
+            .members
+            .iter()
+            .flat_map(|m| m.stars.keys().cloned())
+            .collect();
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Unix time the puzzle for `day` unlocked: midnight in UTC-5.
+pub fn unlock_time(year: u16, day: u8) -> i64 {
+    days_from_civil(year as i64, 12, day as i64) * 86_400 + 5 * 3600
+}
+
+/// One member's result for a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solve<'a> {
+    pub member: &'a Member,
+    /// 1 for the fastest to finish.
+    pub rank: usize,
+    /// Unix times of each star.
+    pub stars: [Option<i64>; 2],
+    /// Seconds from unlocking to each star.
+    pub elapsed: [Option<i64>; 2],
+}
+
+impl Solve<'_> {
+    /// Seconds between the first star and the second.
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.stars[1]? - self.stars[0]?)
+    }
+}
+
+/// Everyone with a star on `day`, both stars first by when they got the second, then one star by
+/// when they got that.
+pub fn rankings(board: &Leaderboard, day: u8) -> Vec<Solve<'_>> {
+    let unlock = unlock_time(board.year, day);
+    let mut solves: Vec<Solve> = board
+        .members
+        .iter()
+        .filter_map(|member| {
+            let stars = *member.stars.get(&day)?;
+            stars[0]?;
+            Some(Solve {
+                member,
+                rank: 0,
+                stars,
+                elapsed: [stars[0].map(|t| t - unlock), stars[1].map(|t| t - unlock)],
+            })
+        })
+        .collect();
+    solves.sort_by_key(|s| (s.stars[1].is_none(), s.stars[1].or(s.stars[0]), s.member.id));
+    for (idx, solve) in solves.iter_mut().enumerate() {
+        solve.rank = idx + 1;
+    }
+    solves
+}
+
+/// A duration like `1:02:03`, with as many hours as it takes.
+fn clock(seconds: Option<i64>) -> String {
+    match seconds {
+        Some(s) if s >= 0 => format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60),
+        Some(_) => "early".to_string(),
+        None => "-".to_string(),
+    }
+}
+
+pub fn print_table(board: &Leaderboard, days: &[u8]) {
+    for &day in days {
+        let solves = rankings(board, day);
+        if solves.is_empty() {
+            continue;
+        }
+        println!("{} day {:02}", board.year, day);
+        println!(
+            "{:>4}  {:<24} {:>10} {:>10} {:>10}",
+            "rank", "member", "part 1", "part 2", "delta"
+        );
+        for solve in solves {
+            println!(
+                "{:>4}  {:<24} {:>10} {:>10} {:>10}",
+                solve.rank,
+                solve.member.name,
+                clock(solve.elapsed[0]),
+                clock(solve.elapsed[1]),
+                clock(solve.delta()),
+            );
+        }
+        println!();
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// One row per member and day, times in seconds and empty where there's no star.
+pub fn write_csv<W: Write>(board: &Leaderboard, days: &[u8], mut out: W) -> io::Result<()> {
+    writeln!(
+        out,
+        "year,day,rank,member_id,member,part1_ts,part2_ts,part1_elapsed,part2_elapsed,delta"
+    )?;
+    let cell = |n: Option<i64>| n.map_or(String::new(), |n| n.to_string());
+    for &day in days {
+        for solve in rankings(board, day) {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{}",
+                board.year,
+                day,
+                solve.rank,
+                solve.member.id,
+                csv_field(&solve.member.name),
+                cell(solve.stars[0]),
+                cell(solve.stars[1]),
+                cell(solve.elapsed[0]),
+                cell(solve.elapsed[1]),
+                cell(solve.delta()),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Where the leaderboard fetched from `url` is kept between runs, like
+/// `inputs/2018/leaderboard-123456-<hash>.json`. The name starts with the leaderboard's id, and
+/// the hash of the whole URL keeps boards from different places apart.
+pub fn cache_path(year: u16, url: &str) -> PathBuf {
+    let file = url.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    let id: String = file
+        .trim_end_matches(".json")
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+    // FNV-1a, which unlike the std hasher is the same from one build to the next
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    Path::new(INPUTS_DIR)
+        .join(year.to_string())
+        .join(format!("leaderboard-{}-{:016x}.json", id, hash))
+}
+
+/// The JSON export at `url`, reusing the copy at `cache` if it was fetched recently enough.
+pub fn fetch(config: &Config, url: &str, cache: &Path) -> Result<String, String> {
+    let fresh = fs::metadata(cache)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .is_some_and(|age| age < REFRESH);
+    if fresh {
+        return fs::read_to_string(cache)
+            .map_err(|e| format!("Couldn't read {}: {}", cache.display(), e));
+    }
+    let json = match fetch::agent()
+        .get(url)
+        .set("Cookie", &config.cookie()?)
+        .call()
+    {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| format!("Couldn't read {}: {}", url, e))?,
+        Err(ureq::Error::Status(code, _)) => {
+            return Err(format!("Couldn't fetch {}: HTTP {}", url, code))
+        }
+        Err(e) => return Err(format!("Couldn't fetch {}: {}", url, e)),
+    };
+    // a session that can't see the leaderboard is sent to the login page instead
+    Leaderboard::parse(&json).map_err(|e| format!("{} isn't a leaderboard export: {}", url, e))?;
+    if let Some(dir) = cache.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    }
+    fs::write(cache, &json).map_err(|e| format!("Couldn't write {}: {}", cache.display(), e))?;
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub;
+
+    // Dec 1st 2018 unlocked at 1543640400
+    const EXPORT: &str = r#"{"event": "2018", "owner_id": 1, "members": {
+        "1": {"id": 1, "name": "Evan", "stars": 3, "completion_day_level": {
+            "1": {"1": {"get_star_ts": 1543640700}, "2": {"get_star_ts": 1543641000}},
+            "2": {"1": {"get_star_ts": "1543730000"}}}},
+        "2": {"id": "2", "name": null, "stars": 2, "completion_day_level": {
+            "1": {"1": {"get_star_ts": 1543640460}, "2": {"get_star_ts": 1543644000}}}},
+        "3": {"id": 3, "name": "Late, \"Sam\"", "stars": 1, "completion_day_level": {
+            "1": {"1": {"get_star_ts": 1543640520}}}}
+    }}"#;
+
+    #[test]
+    fn unlocks_at_midnight_eastern() {
+        assert_eq!(unlock_time(2018, 1), 1_543_640_400);
+        assert_eq!(unlock_time(2018, 25) - unlock_time(2018, 1), 24 * 86_400);
+        assert_eq!(unlock_time(2020, 1), 1_606_798_800);
+    }
+
+    #[test]
+    fn reads_exports() {
+        let board = Leaderboard::parse(EXPORT).unwrap();
+        assert_eq!(board.year, 2018);
+        assert_eq!(board.days(), vec![1, 2]);
+        let names: Vec<&str> = board.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Evan", "(anonymous user #2)", "Late, \"Sam\""]);
+        assert_eq!(board.members[0].stars[&2], [Some(1_543_730_000), None]);
+        assert!(Leaderboard::parse("{}").is_err());
+        assert!(Leaderboard::parse(r#"{"event": "67554", "members": {}}"#).is_err());
+        let negative = r#"{"event": 2018, "members": {"1": {"id": -1}}}"#;
+        assert!(Leaderboard::parse(negative).unwrap_err().contains("id -1"));
+    }
+
+    #[test]
+    fn ranks_by_second_star() {
+        let board = Leaderboard::parse(EXPORT).unwrap();
+        let solves = rankings(&board, 1);
+        let order: Vec<(usize, u64)> = solves.iter().map(|s| (s.rank, s.member.id)).collect();
+        assert_eq!(order, vec![(1, 1), (2, 2), (3, 3)]);
+        assert_eq!(solves[0].elapsed, [Some(300), Some(600)]);
+        assert_eq!(solves[0].delta(), Some(300));
+        assert_eq!(solves[2].delta(), None);
+        assert_eq!(clock(solves[1].delta()), "0:59:00");
+        assert_eq!(clock(Some(90_061)), "25:01:01");
+    }
+
+    #[test]
+    fn exports_csv() {
+        let board = Leaderboard::parse(EXPORT).unwrap();
+        let mut out = Vec::new();
+        write_csv(&board, &[1], &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            "2018,1,1,1,Evan,1543640700,1543641000,300,600,300"
+        );
+        assert_eq!(
+            lines[3],
+            "2018,1,3,3,\"Late, \"\"Sam\"\"\",1543640520,,120,,"
+        );
+    }
+
+    #[test]
+    fn caches_each_board_separately() {
+        let board = |id| {
+            format!(
+                "https://adventofcode.com/2018/leaderboard/private/view/{}.json",
+                id
+            )
+        };
+        let first = cache_path(2018, &board(123));
+        assert!(first.starts_with("inputs/2018"));
+        let name = first.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("leaderboard-123-"), "{}", name);
+        assert_ne!(first, cache_path(2018, &board(456)));
+        assert_ne!(
+            first,
+            cache_path(
+                2018,
+                "http://localhost:1/2018/leaderboard/private/view/123.json"
+            )
+        );
+        assert_eq!(first, cache_path(2018, &board(123)));
+    }
+
+    #[test]
+    fn fetches_with_the_session_and_caches() {
+        let (base_url, server) = stub::server(1, "200 OK", EXPORT);
+        let config = Config {
+            session: Some("abc123".to_string()),
+            base_url,
+            leaderboard: Some("1".to_string()),
+        };
+        let url = config.leaderboard_url(2018).unwrap();
+        let dir = stub::scratch("leaderboard");
+        let cache = dir.join(cache_path(2018, &url));
+        assert_eq!(fetch(&config, &url, &cache).unwrap(), EXPORT);
+        assert_eq!(fetch(&config, &url, &cache).unwrap(), EXPORT);
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2018/leaderboard/private/view/1.json"));
+        assert!(requests[0]
+            .to_lowercase()
+            .contains("cookie: session=abc123"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
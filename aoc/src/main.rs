@@ -0,0 +1,449 @@
+//! Runs any selection of the solved days through their common `Solution` interface.
+
+mod alloc;
+mod answers;
+mod bench;
+mod cli;
+mod days;
+mod fetch;
+mod json;
+mod leaderboard;
+mod parallel;
+mod scaffold;
+#[cfg(test)]
+mod stub;
+mod submit;
+
+use advent_common::input::{self, Source};
+use advent_common::{Answer, Part, Puzzle};
+use answers::Answers;
+use cli::{Command, Selection};
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+fn print_answer(puzzle: &dyn Puzzle, part: Part, answer: &Answer) {
+    let label = format!("{} day {:02} part {}:", puzzle.year(), puzzle.day(), part);
+    match answer {
+        Answer::Art(art) => println!("{}\n{}", label, art),
+        _ => println!("{} {}", label, answer),
+    }
+}
+
+/// The days a selection covers, or `None` after explaining why it can't be used.
+fn selected(selection: &Selection) -> Option<Vec<&'static dyn Puzzle>> {
+    let puzzles: Vec<&dyn Puzzle> = days::DAYS
+        .iter()
+        .cloned()
+        .filter(|p| selection.matches(*p))
+        .collect();
+    if puzzles.is_empty() {
+        eprintln!("No solutions match that selection");
+        return None;
+    }
+    if selection.input.is_some() && puzzles.len() > 1 {
+        eprintln!("--input can only be used when a single day is selected");
+        return None;
+    }
+    Some(puzzles)
+}
+
+/// Read a day's input, or explain why it couldn't be.
+fn read_input(puzzle: &dyn Puzzle, selection: &Selection) -> Result<(Source, String), String> {
+    let source = Source::resolve(selection.input.as_deref(), puzzle.year(), puzzle.day());
+    match source.read() {
+        Ok(raw) => Ok((source, raw)),
+        Err(e) => Err(format!("Couldn't read {}: {}", source, e)),
+    }
+}
+
+/// How long a phase took, and what it allocated when that's being counted.
+pub struct Phase {
+    pub elapsed: Duration,
+    pub memory: Option<alloc::Usage>,
+}
+
+fn phase<T, F: FnOnce() -> T>(f: F) -> (T, Phase) {
+    let start = Instant::now();
+    let (result, memory) = alloc::measure(f);
+    let elapsed = start.elapsed();
+    (result, Phase { elapsed, memory })
+}
+
+/// A day's answers and how long each phase took to produce them.
+pub struct Solved {
+    pub parse: Phase,
+    pub parts: Vec<(Part, Answer, Phase)>,
+}
+
+fn solve_day(puzzle: &dyn Puzzle, selection: &Selection) -> Result<Solved, String> {
+    parallel::isolate(|| solve_input(puzzle, selection)).unwrap_or_else(|panic| {
+        Err(format!(
+            "{} day {:02} panicked: {}",
+            puzzle.year(),
+            puzzle.day(),
+            panic
+        ))
+    })
+}
+
+fn solve_input(puzzle: &dyn Puzzle, selection: &Selection) -> Result<Solved, String> {
+    let (source, raw) = read_input(puzzle, selection)?;
+    let (parsed, parse) = phase(|| puzzle.parse(&raw));
+    let parsed = parsed.map_err(|e| format!("Couldn't parse {}: {}", source, e))?;
+    let parts = selection
+        .parts()
+        .into_iter()
+        .map(|part| {
+            let (answer, phase) = phase(|| puzzle.solve(parsed.as_ref(), part));
+            (part, answer, phase)
+        })
+        .collect();
+    Ok(Solved { parse, parts })
+}
+
+/// Solve every selected day, returning false if any of them couldn't be run.
+fn run(selection: &Selection, options: &cli::Run) -> bool {
+    let mut ok = true;
+    let puzzles = match selected(selection) {
+        Some(puzzles) => puzzles,
+        None => return false,
+    };
+    let as_json = options.json;
+    let mut results = Vec::new();
+    let solve = |puzzle: &&dyn Puzzle| solve_day(*puzzle, selection);
+    parallel::map_ordered(&puzzles, options.jobs, solve, |puzzle, solved| {
+        let puzzle = *puzzle;
+        match solved {
+            Ok(solved) if as_json => results.push(json::solved(puzzle, &solved)),
+            Ok(solved) => {
+                for (part, answer, _) in &solved.parts {
+                    print_answer(puzzle, *part, answer);
+                }
+            }
+            Err(e) => {
+                ok = false;
+                if as_json {
+                    results.push(json::failed(puzzle, &e));
+                } else {
+                    eprintln!("{}", e);
+                }
+            }
+        }
+    });
+    if as_json {
+        println!("{}", json::document(results));
+    }
+    ok
+}
+
+/// Time every selected day and print a summary table, returning false if any couldn't be run.
+fn bench(selection: &Selection, options: &bench::Options) -> bool {
+    let mut ok = true;
+    let puzzles = match selected(selection) {
+        Some(puzzles) => puzzles,
+        None => return false,
+    };
+    let mut rows: Vec<(&dyn Puzzle, bench::Timings)> = Vec::new();
+    for puzzle in puzzles {
+        let (source, raw) = match read_input(puzzle, selection) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("{}", e);
+                ok = false;
+                continue;
+            }
+        };
+        eprintln!("Benchmarking {} day {:02}", puzzle.year(), puzzle.day());
+        // a day that panics is left out of the table rather than taking it down
+        let timings = parallel::isolate(|| bench::bench(puzzle, &raw, &selection.parts(), options));
+        match timings {
+            Ok(Ok(timings)) => rows.push((puzzle, timings)),
+            Ok(Err(e)) => {
+                eprintln!("Couldn't parse {}: {}", source, e);
+                ok = false;
+            }
+            Err(panic) => {
+                eprintln!(
+                    "{} day {:02} panicked: {}",
+                    puzzle.year(),
+                    puzzle.day(),
+                    panic
+                );
+                ok = false;
+            }
+        }
+    }
+    bench::print_table(&rows);
+    ok
+}
+
+fn print_mismatch(label: &str, expected: &Answer, actual: &Answer) {
+    match (expected, actual) {
+        (Answer::Int(_), Answer::Int(_)) | (Answer::Text(_), Answer::Text(_)) => {
+            println!("{} FAIL expected {}, got {}", label, expected, actual)
+        }
+        _ => println!("{} FAIL\nexpected:\n{}\ngot:\n{}", label, expected, actual),
+    }
+}
+
+/// Check every selected day against the recorded answers, returning false on any failure.
+fn verify(selection: &Selection, answers_path: &Path) -> bool {
+    let answers = match Answers::load(answers_path) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+    let puzzles = match selected(selection) {
+        Some(puzzles) => puzzles,
+        None => return false,
+    };
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for puzzle in puzzles {
+        let parsed = read_input(puzzle, selection).and_then(|(source, raw)| {
+            parallel::isolate(|| puzzle.parse(&raw))
+                .map_err(|panic| format!("parsing {} panicked: {}", source, panic))?
+                .map_err(|e| format!("Couldn't parse {}: {}", source, e))
+        });
+        for part in selection.parts() {
+            let label = format!("{} day {:02} part {}:", puzzle.year(), puzzle.day(), part);
+            let parsed = match parsed {
+                Ok(ref parsed) => parsed,
+                Err(ref e) => {
+                    println!("{} FAIL {}", label, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            // a day that panics is a failure, but shouldn't stop the others being checked
+            let actual = match parallel::isolate(|| puzzle.solve(parsed.as_ref(), part)) {
+                Ok(actual) => actual,
+                Err(panic) => {
+                    println!("{} FAIL panicked: {}", label, panic);
+                    failed += 1;
+                    continue;
+                }
+            };
+            match answers.get(puzzle.year(), puzzle.day(), part) {
+                Some(ref expected) if answers::matches(expected, &actual) => {
+                    println!("{} pass", label);
+                    passed += 1;
+                }
+                Some(expected) => {
+                    print_mismatch(&label, &expected, &actual);
+                    failed += 1;
+                }
+                None => {
+                    println!("{} missing", label);
+                    missing += 1;
+                }
+            }
+        }
+    }
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    failed == 0
+}
+
+/// Print generated input for the selected day, returning false if it has no generator.
+fn generate(selection: &Selection, options: &cli::Generate) -> bool {
+    let puzzle = match selected(selection) {
+        Some(puzzles) => puzzles[0],
+        None => return false,
+    };
+    match puzzle.generate(options.seed, options.size) {
+        Some(input) => {
+            println!("{}", input);
+            true
+        }
+        None => {
+            eprintln!(
+                "{} day {:02} has no input generator",
+                puzzle.year(),
+                puzzle.day()
+            );
+            false
+        }
+    }
+}
+
+/// Download every input in `days` of `year` that isn't on disk yet, returning false if any
+/// couldn't be.
+fn fetch_inputs(year: u16, (first, last): (u8, u8)) -> bool {
+    let config = match fetch::Config::load(&fetch::default_config_path()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+    for day in first..=last {
+        let path = input::default_path(year, day);
+        match fetch::fetch(&config, year, day, &path) {
+            Ok(fetch::Fetched::Cached) => println!("{} already there", path.display()),
+            Ok(fetch::Fetched::Downloaded(bytes)) => {
+                println!("wrote {} ({} bytes)", path.display(), bytes)
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                // later days won't be any more available than this one
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// The answer to send for the selected part, solving it unless one was given.
+fn answer_to_submit(selection: &Selection, answer: Option<String>) -> Result<String, String> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let puzzle =
+                selected(selection).ok_or("Nothing to solve, pass the answer with --answer")?[0];
+            let solved = solve_day(puzzle, selection)?;
+            match &solved.parts[0].1 {
+                Answer::Art(art) => {
+                    return Err(format!(
+                        "{}\nThat has to be read by a human, pass what it says with --answer",
+                        art
+                    ))
+                }
+                Answer::Unsolved(why) => return Err(format!("There's no answer to send: {}", why)),
+                answer => answer.to_string(),
+            }
+        }
+    };
+    // a blank guess would only count against the lockout
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Err("There's no answer to send, it's empty".to_string());
+    }
+    Ok(answer.to_string())
+}
+
+/// Send an answer for the selected part, returning false unless it was right.
+fn submit_answer(selection: &Selection, answer: Option<String>) -> bool {
+    let (year, (day, _), part) = match (selection.year, selection.days, selection.part) {
+        (Some(year), Some(days), Some(part)) => (year, days, part),
+        _ => unreachable!("the command line makes sure there's a day and part"),
+    };
+    let submitted = answer_to_submit(selection, answer).and_then(|answer| {
+        let config = fetch::Config::load(&fetch::default_config_path())?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let files = submit::Files::default();
+        let verdict = submit::submit(&config, &files, (year, day, part), &answer, now)?;
+        Ok((answer, verdict))
+    });
+    match submitted {
+        Ok((answer, verdict)) => {
+            println!(
+                "{} day {:02} part {}: {} is {}",
+                year, day, part, answer, verdict
+            );
+            verdict == submit::Verdict::Correct
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
+
+/// Read or download the leaderboard export the options ask for.
+fn leaderboard_json(year: Option<u16>, options: &cli::Stats) -> Result<String, String> {
+    if let Some(path) = &options.file {
+        return fs::read_to_string(path)
+            .map_err(|e| format!("Couldn't read {}: {}", path.display(), e));
+    }
+    let year = year.expect("the command line makes sure there's a year to download");
+    let config = fetch::Config::load(&fetch::default_config_path())?;
+    let url = match &options.url {
+        Some(url) => url.clone(),
+        None => config.leaderboard_url(year)?,
+    };
+    leaderboard::fetch(&config, &url, &leaderboard::cache_path(year, &url))
+}
+
+/// Print leaderboard statistics for the selected days.
+fn stats(selection: &Selection, options: &cli::Stats) -> bool {
+    let board = leaderboard_json(selection.year, options)
+        .and_then(|json| leaderboard::Leaderboard::parse(&json));
+    let board = match board {
+        Ok(board) if selection.year.is_none_or(|y| y == board.year) => board,
+        Ok(board) => {
+            eprintln!("That leaderboard is for {}", board.year);
+            return false;
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+    let days: Vec<u8> = board
+        .days()
+        .into_iter()
+        .filter(|d| {
+            selection
+                .days
+                .is_none_or(|(first, last)| (first..=last).contains(d))
+        })
+        .collect();
+    if options.csv {
+        let stdout = std::io::stdout();
+        if let Err(e) = leaderboard::write_csv(&board, &days, stdout.lock()) {
+            eprintln!("Couldn't write CSV: {}", e);
+            return false;
+        }
+    } else {
+        leaderboard::print_table(&board, &days);
+    }
+    true
+}
+
+/// Scaffold a new day in the workspace in the current directory.
+fn new_day(year: u16, day: u8) -> bool {
+    match scaffold::new_day(Path::new("."), year, day) {
+        Ok(written) => {
+            for path in written {
+                println!("wrote {}", path.display());
+            }
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
+
+fn main() {
+    let command = match cli::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+    let ok = match command {
+        Command::Run(selection, options) => run(&selection, &options),
+        Command::Bench(selection, options) => bench(&selection, &options),
+        Command::Verify(selection, answers) => verify(&selection, &answers),
+        Command::Generate(selection, options) => generate(&selection, &options),
+        Command::New(year, day) => new_day(year, day),
+        Command::Fetch(year, days) => fetch_inputs(year, days),
+        Command::Submit(selection, answer) => submit_answer(&selection, answer),
+        Command::Stats(selection, options) => stats(&selection, &options),
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            true
+        }
+    };
+    if !ok {
+        process::exit(1);
+    }
+}
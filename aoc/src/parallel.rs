@@ -0,0 +1,103 @@
+//! Running days side by side while still reporting them in order.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Run `f`, catching a panic and returning its message instead.
+pub fn isolate<T, F: FnOnce() -> T>(f: F) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(message)
+}
+
+fn message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(s) => s.to_string(),
+            Err(_) => "unknown reason".to_string(),
+        },
+    }
+}
+
+/// Apply `f` to every item on up to `jobs` threads, and hand each result to `report` in the
+/// order of the items, as soon as everything before it is done.
+pub fn map_ordered<T, R, F, P>(items: &[T], jobs: usize, f: F, mut report: P)
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    P: FnMut(&T, R),
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        for item in items {
+            report(item, f(item));
+        }
+        return;
+    }
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (next, f) = (&next, &f);
+            scope.spawn(move || loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                if idx >= items.len() {
+                    break;
+                }
+                if sender.send((idx, f(&items[idx]))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        let mut pending: Vec<Option<R>> = items.iter().map(|_| None).collect();
+        let mut reported = 0;
+        for (idx, result) in receiver {
+            pending[idx] = Some(result);
+            while let Some(result) = pending.get_mut(reported).and_then(Option::take) {
+                report(&items[reported], result);
+                reported += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn reports_in_order() {
+        let items: Vec<u64> = (0..20).collect();
+        let mut seen = Vec::new();
+        map_ordered(
+            &items,
+            4,
+            |n| {
+                // later items finish first
+                thread::sleep(Duration::from_millis(20 - n));
+                n * 2
+            },
+            |n, doubled| seen.push((*n, doubled)),
+        );
+        assert_eq!(seen, items.iter().map(|n| (*n, n * 2)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        assert_eq!(
+            isolate(|| panic!("no min x?")),
+            Err::<(), _>("no min x?".to_string())
+        );
+        assert_eq!(
+            isolate(|| panic!("{} left", 3)),
+            Err::<(), _>("3 left".to_string())
+        );
+        assert_eq!(isolate(|| 1), Ok(1));
+    }
+}
@@ -0,0 +1,233 @@
+//! `aoc new`: a fresh day crate laid out like the others and registered with the runner.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = r#"[package]
+name = "advent-{year}-{dd}"
+version = "0.1.0"
+authors = ["Evan McClain <aeroevan@gmail.com>"]
+edition = "2018"
+
+[dependencies]
+advent-common = { path = "../../common" }
+"#;
+
+const MAIN: &str = "fn main() {
+    advent_common::run::<advent_{year}_{dd}::Day{dd}>();
+}
+";
+
+const LIB: &str = r#"use advent_common::{input, Answer, ParseError, Solution};
+
+pub struct Day{dd};
+
+impl Solution for Day{dd} {
+    const YEAR: u16 = {year};
+    const DAY: u8 = {day};
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+        input::parse_lines(input, |l| Ok(l.to_string()))
+    }
+
+    fn part1(_lines: &Vec<String>) -> Answer {
+        todo!()
+    }
+
+    fn part2(_lines: &Vec<String>) -> Answer {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn parses_example() {
+        assert!(Day{dd}::parse(EXAMPLE).is_ok());
+    }
+}
+"#;
+
+/// Fill in the `{year}`, `{day}` and zero padded `{dd}` of a template.
+fn fill(template: &str, year: u16, day: u8) -> String {
+    template
+        .replace("{year}", &year.to_string())
+        .replace("{dd}", &format!("{:02}", day))
+        .replace("{day}", &day.to_string())
+}
+
+/// Insert `line` among the lines `key` recognises, keeping them sorted by that key.
+fn insert_sorted<K, F>(text: &str, line: &str, new_key: K, key: F) -> Result<String, String>
+where
+    K: Ord,
+    F: Fn(&str) -> Option<K>,
+{
+    let mut lines: Vec<&str> = text.lines().collect();
+    let mut at = None;
+    for (idx, existing) in lines.iter().enumerate() {
+        match key(existing) {
+            Some(k) if k == new_key => return Err("it's already there".to_string()),
+            Some(k) if k > new_key => {
+                at = Some(idx);
+                break;
+            }
+            Some(_) => at = Some(idx + 1),
+            None => {}
+        }
+    }
+    let at = at.ok_or("there's nothing like it to add it next to")?;
+    lines.insert(at, line);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The year and day in text like `2018_07` or `2018-07`, with the day defaulting to 0.
+fn year_day(text: &str, separator: char) -> Option<(u16, u8)> {
+    let year = text.get(..4)?.parse().ok()?;
+    match text[4..].strip_prefix(separator) {
+        Some(rest) => Some((year, rest.get(..2)?.parse().ok()?)),
+        None => Some((year, 0)),
+    }
+}
+
+fn add_member(manifest: &str, year: u16) -> Result<String, String> {
+    let glob = format!("    \"{}/*\",", year);
+    let member = |l: &str| {
+        let glob = l.trim().strip_prefix('"')?.strip_suffix("/*\",")?;
+        glob.parse::<u16>().ok()
+    };
+    if manifest.lines().any(|l| member(l) == Some(year)) {
+        return Ok(manifest.to_string());
+    }
+    insert_sorted(manifest, &glob, year, member)
+}
+
+fn add_dependency(manifest: &str, year: u16, day: u8) -> Result<String, String> {
+    let line = format!(
+        "advent-{}-{:02} = {{ path = \"../{}/{:02}\" }}",
+        year, day, year, day
+    );
+    insert_sorted(manifest, &line, (year, day), |l| {
+        year_day(l.strip_prefix("advent-")?, '-').filter(|(_, d)| *d > 0)
+    })
+}
+
+fn add_day(days: &str, year: u16, day: u8) -> Result<String, String> {
+    let line = format!("    &advent_{}_{:02}::Day{:02},", year, day, day);
+    insert_sorted(days, &line, (year, day), |l| {
+        year_day(l.trim().strip_prefix("&advent_")?, '_')
+    })
+}
+
+/// Read `path` under `root` and apply `edit`, explaining any failure. `None` if the edit left
+/// the file as it was.
+fn edited<F>(root: &Path, path: &str, edit: F) -> Result<Option<(PathBuf, String)>, String>
+where
+    F: FnOnce(&str) -> Result<String, String>,
+{
+    let path = root.join(path);
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    let edited = edit(&text).map_err(|e| format!("Couldn't update {}: {}", path.display(), e))?;
+    if edited == text {
+        return Ok(None);
+    }
+    Ok(Some((path, edited)))
+}
+
+/// Create the crate for `day` of `year` under the workspace at `root` and register it, returning
+/// every file written.
+pub fn new_day(root: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>, String> {
+    let dir = root.join(year.to_string()).join(format!("{:02}", day));
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+    // work out every edit before writing anything, so a failure leaves the tree alone
+    let mut files: Vec<(PathBuf, String)> = vec![
+        edited(root, "Cargo.toml", |t| add_member(t, year))?,
+        edited(root, "aoc/Cargo.toml", |t| add_dependency(t, year, day))?,
+        edited(root, "aoc/src/days.rs", |t| add_day(t, year, day))?,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    files.push((dir.join("Cargo.toml"), fill(MANIFEST, year, day)));
+    files.push((dir.join("src/main.rs"), fill(MAIN, year, day)));
+    files.push((dir.join("src/lib.rs"), fill(LIB, year, day)));
+
+    fs::create_dir_all(dir.join("src"))
+        .map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+    let mut written = Vec::new();
+    for (path, text) in files {
+        fs::write(&path, text).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub;
+
+    #[test]
+    fn fills_templates() {
+        let main = fill(MAIN, 2019, 3);
+        assert!(main.contains("advent_2019_03::Day03"));
+        let lib = fill(LIB, 2019, 3);
+        assert!(lib.contains("const YEAR: u16 = 2019;"));
+        assert!(lib.contains("const DAY: u8 = 3;"));
+        assert!(lib.contains("impl Solution for Day03 {"));
+    }
+
+    #[test]
+    fn registers_days_in_order() {
+        let days = "pub static DAYS: &[&dyn Puzzle] = &[\n    &advent_2018_01::Day01,\n    &advent_2018_10::Day10,\n];\n";
+        let added = add_day(days, 2018, 7).unwrap();
+        assert!(added.contains("Day01,\n    &advent_2018_07::Day07,\n    &advent_2018_10"));
+        let added = add_day(days, 2019, 1).unwrap();
+        assert!(added.contains("Day10,\n    &advent_2019_01::Day01,\n];"));
+        assert!(add_day(days, 2018, 10).is_err());
+    }
+
+    #[test]
+    fn adds_dependencies_and_members() {
+        let manifest = "[dependencies]\nadvent-common = { path = \"../common\" }\nadvent-2018-01 = { path = \"../2018/01\" }\ntoml = \"0.8\"\n";
+        let added = add_dependency(manifest, 2018, 2).unwrap();
+        assert!(added.contains("2018/01\" }\nadvent-2018-02 = { path = \"../2018/02\" }\ntoml"));
+
+        let workspace = "[workspace]\nmembers = [\n    \"aoc\",\n    \"2018/*\",\n]\n";
+        assert_eq!(add_member(workspace, 2018).unwrap(), workspace);
+        let added = add_member(workspace, 2019).unwrap();
+        assert!(added.contains("\"2018/*\",\n    \"2019/*\",\n]"));
+    }
+
+    #[test]
+    fn leaves_unchanged_files_alone() {
+        let root = stub::scratch("scaffold");
+        let workspace = "[workspace]\nmembers = [\n    \"aoc\",\n    \"2018/*\",\n]\n";
+        fs::write(root.join("Cargo.toml"), workspace).unwrap();
+        fs::create_dir_all(root.join("aoc/src")).unwrap();
+        fs::write(
+            root.join("aoc/Cargo.toml"),
+            "[dependencies]\nadvent-2018-01 = { path = \"../2018/01\" }\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("aoc/src/days.rs"),
+            "pub static DAYS: &[&dyn Puzzle] = &[\n    &advent_2018_01::Day01,\n];\n",
+        )
+        .unwrap();
+        let written = new_day(&root, 2018, 2).unwrap();
+        assert!(!written.contains(&root.join("Cargo.toml")));
+        assert!(written.contains(&root.join("aoc/Cargo.toml")));
+        let manifest = fs::read_to_string(root.join("2018/02/Cargo.toml")).unwrap();
+        let existing = include_str!("../../2018/01/Cargo.toml");
+        assert_eq!(manifest, existing.replace("2018-01", "2018-02"));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,466 @@
+//! Submitting answers to the puzzle site, and remembering what it said.
+//!
+//! Right answers go into the answers file `aoc verify` checks against. Wrong ones, and whether
+//! they were too high or too low, go into `inputs/guesses.toml` along with when the site will
+//! take another answer, so a guess that is already known to be wrong is never sent again:
+//!
+//! ```toml
+//! wait_until = 1544000000
+//!
+//! [2018.01]
+//! part1_wrong = ["1234", "12"]
+//! part1_too_high = 1234
+//! part1_too_low = 12
+//! ```
+
+use crate::answers::{self, Answers};
+use crate::fetch::{self, Config};
+use advent_common::input::INPUTS_DIR;
+use advent_common::{Answer, Part};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+pub fn default_guesses_path() -> PathBuf {
+    Path::new(INPUTS_DIR).join("guesses.toml")
+}
+
+/// What the site made of an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without saying which way.
+    Wrong,
+    /// Answered too recently, try again after this many seconds.
+    Wait(u64),
+    /// The part is already solved, or not unlocked yet.
+    AlreadySolved,
+    /// Anything else, with the text of the response.
+    Unknown(String),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::Wait(seconds) => write!(f, "not checked, wait {}s first", seconds),
+            Verdict::AlreadySolved => write!(f, "not checked, already solved or not unlocked"),
+            Verdict::Unknown(text) => write!(f, "not understood: {}", text),
+        }
+    }
+}
+
+/// The readable text of the page's `<article>`, which is where the verdict is.
+fn article_text(html: &str) -> String {
+    let start = html.find("<article").unwrap_or(0);
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |e| start + e);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html[start..end].chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Seconds in a wait like `1m 5s`, or the `one minute` and `5 minutes` of a wrong answer.
+fn wait_seconds(text: &str) -> Option<u64> {
+    let lower = text.to_lowercase();
+    if let Some(end) = lower.find(" left to wait") {
+        let start = lower[..end].rfind("you have ")? + "you have ".len();
+        let mut seconds = 0;
+        for token in lower[start..end].split_whitespace() {
+            let unit = token.chars().last()?;
+            let n: u64 = token[..token.len() - unit.len_utf8()].parse().ok()?;
+            seconds += match unit {
+                'h' => n * 3600,
+                'm' => n * 60,
+                's' => n,
+                _ => return None,
+            };
+        }
+        return Some(seconds);
+    }
+    let idx = lower.find("please wait ")?;
+    let mut words = lower[idx + "please wait ".len()..].split_whitespace();
+    let n = match words.next()? {
+        "one" | "a" => 1,
+        n => n.parse().ok()?,
+    };
+    match words.next()? {
+        w if w.starts_with("minute") => Some(n * 60),
+        w if w.starts_with("second") => Some(n),
+        _ => None,
+    }
+}
+
+/// Read the verdict out of the page the site responds to an answer with.
+pub fn parse_response(html: &str) -> Verdict {
+    let text = article_text(html);
+    let lower = text.to_lowercase();
+    if lower.contains("that's the right answer") {
+        Verdict::Correct
+    } else if lower.contains("that's not the right answer") {
+        if lower.contains("too high") {
+            Verdict::TooHigh
+        } else if lower.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if lower.contains("answer too recently") {
+        Verdict::Wait(wait_seconds(&text).unwrap_or(60))
+    } else if lower.contains("solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown(text)
+    }
+}
+
+/// Everything the site has said about wrong answers so far.
+#[derive(Debug, Default)]
+pub struct Guesses {
+    table: Table,
+}
+
+impl Guesses {
+    /// Load the guesses file, treating a missing file as no guesses yet.
+    pub fn load(path: &Path) -> Result<Guesses, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Guesses::default()),
+            Err(e) => return Err(format!("Couldn't read {}: {}", path.display(), e)),
+        };
+        let table = contents
+            .parse::<Table>()
+            .map_err(|e| format!("Couldn't parse {}: {}", path.display(), e))?;
+        Ok(Guesses { table })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+        }
+        fs::write(path, self.table.to_string())
+            .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+    }
+
+    /// Unix time the site said to wait until before answering again.
+    pub fn wait_until(&self) -> u64 {
+        self.table
+            .get("wait_until")
+            .and_then(Value::as_integer)
+            .unwrap_or(0) as u64
+    }
+
+    fn set_wait_until(&mut self, time: u64) {
+        self.table
+            .insert("wait_until".to_string(), Value::Integer(time as i64));
+    }
+
+    fn day(&self, year: u16, day: u8) -> Option<&Table> {
+        self.table
+            .get(&year.to_string())?
+            .get(format!("{:02}", day))?
+            .as_table()
+    }
+
+    fn day_mut(&mut self, year: u16, day: u8) -> &mut Table {
+        let mut table = &mut self.table;
+        for key in [year.to_string(), format!("{:02}", day)] {
+            let entry = table
+                .entry(key)
+                .or_insert_with(|| Value::Table(Table::new()));
+            if !entry.is_table() {
+                *entry = Value::Table(Table::new());
+            }
+            table = entry.as_table_mut().expect("just made sure it's a table");
+        }
+        table
+    }
+
+    /// Why `answer` is already known to be wrong, if it is.
+    pub fn known_wrong(&self, year: u16, day: u8, part: Part, answer: &str) -> Option<String> {
+        let day = self.day(year, day)?;
+        let get = |what: &str| day.get(&format!("part{}_{}", part, what));
+        let wrong = get("wrong").and_then(Value::as_array);
+        if wrong.is_some_and(|w| w.iter().any(|v| v.as_str() == Some(answer))) {
+            return Some(format!("{} was already wrong", answer));
+        }
+        let n: i64 = answer.parse().ok()?;
+        if let Some(high) = get("too_high").and_then(Value::as_integer) {
+            if n >= high {
+                return Some(format!("{} was too high, so {} is too", high, n));
+            }
+        }
+        if let Some(low) = get("too_low").and_then(Value::as_integer) {
+            if n <= low {
+                return Some(format!("{} was too low, so {} is too", low, n));
+            }
+        }
+        None
+    }
+
+    /// Remember a wrong answer and which way it was off.
+    pub fn record_wrong(
+        &mut self,
+        year: u16,
+        day: u8,
+        part: Part,
+        answer: &str,
+        verdict: &Verdict,
+    ) {
+        let day = self.day_mut(year, day);
+        let wrong = day
+            .entry(format!("part{}_wrong", part))
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(wrong) = wrong {
+            wrong.push(Value::String(answer.to_string()));
+        }
+        let n = match answer.parse::<i64>() {
+            Ok(n) => n,
+            Err(_) => return,
+        };
+        // only the closest bound on either side matters
+        let (key, closer): (_, fn(i64, i64) -> bool) = match verdict {
+            Verdict::TooHigh => ("too_high", |new, old| new < old),
+            Verdict::TooLow => ("too_low", |new, old| new > old),
+            _ => return,
+        };
+        let key = format!("part{}_{}", part, key);
+        if day
+            .get(&key)
+            .and_then(Value::as_integer)
+            .is_none_or(|old| closer(n, old))
+        {
+            day.insert(key, Value::Integer(n));
+        }
+    }
+}
+
+/// Where the submit command reads and records what it knows.
+pub struct Files {
+    pub answers: PathBuf,
+    pub guesses: PathBuf,
+}
+
+impl Default for Files {
+    fn default() -> Files {
+        Files {
+            answers: answers::default_path(),
+            guesses: default_guesses_path(),
+        }
+    }
+}
+
+/// Post `answer` for a part to the site, unless what's already known makes that pointless, and
+/// record what the site said. `now` is the current Unix time.
+pub fn submit(
+    config: &Config,
+    files: &Files,
+    (year, day, part): (u16, u8, Part),
+    answer: &str,
+    now: u64,
+) -> Result<Verdict, String> {
+    let mut answers = Answers::load(&files.answers)?;
+    if let Some(known) = answers.get(year, day, part) {
+        let verdict = if answers::matches(&known, &Answer::Text(answer.to_string())) {
+            "right"
+        } else {
+            "wrong"
+        };
+        return Err(format!(
+            "The answer is already recorded as {}, so {} is {}",
+            known, answer, verdict
+        ));
+    }
+    let mut guesses = Guesses::load(&files.guesses)?;
+    if let Some(reason) = guesses.known_wrong(year, day, part, answer) {
+        return Err(format!("Not submitting, {}", reason));
+    }
+    if now < guesses.wait_until() {
+        return Err(format!(
+            "Not submitting, the site asked to wait {}s more",
+            guesses.wait_until() - now
+        ));
+    }
+
+    let url = config.day_url(year, day, "answer");
+    let level = part.to_string();
+    let response = fetch::agent()
+        .post(&url)
+        .set("Cookie", &config.cookie()?)
+        .send_form(&[("level", &level), ("answer", answer)]);
+    let html = match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| format!("Couldn't read {}: {}", url, e))?,
+        Err(ureq::Error::Status(code, _)) => {
+            return Err(format!("Couldn't submit to {}: HTTP {}", url, code))
+        }
+        Err(e) => return Err(format!("Couldn't submit to {}: {}", url, e)),
+    };
+
+    let verdict = parse_response(&html);
+    match verdict {
+        Verdict::Correct => {
+            let answer = match answer.parse() {
+                Ok(n) => Answer::Int(n),
+                Err(_) => Answer::Text(answer.to_string()),
+            };
+            answers.record(year, day, part, &answer);
+            answers.save(&files.answers)?;
+        }
+        Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong => {
+            guesses.record_wrong(year, day, part, answer, &verdict);
+            // a wrong answer locks answering for a while too
+            if let Some(wait) = wait_seconds(&article_text(&html)) {
+                guesses.set_wait_until(now + wait);
+            }
+            guesses.save(&files.guesses)?;
+        }
+        Verdict::Wait(seconds) => {
+            guesses.set_wait_until(now + seconds);
+            guesses.save(&files.guesses)?;
+        }
+        Verdict::AlreadySolved | Verdict::Unknown(_) => {}
+    }
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub;
+
+    const RIGHT: &str = "<main><article><p>That's the right answer!  You are one gold star \
+        closer to fixing the time stream. <a href=\"/2018/day/1#part2\">[Continue to Part Two]</a>\
+        </p></article></main>";
+    const TOO_HIGH: &str = "<main><article><p>That's not the right answer; your answer is too \
+        high.  If you're stuck, make sure you're using the full input data.  Please wait one \
+        minute before trying again. <a href=\"/2018/day/1\">[Return to Day 1]</a></p></article>\
+        </main>";
+    const TOO_SOON: &str = "<article><p>You gave an answer too recently; you have to wait after \
+        submitting an answer before trying again.  You have 1m 5s left to wait.</p></article>";
+
+    #[test]
+    fn reads_verdicts() {
+        assert_eq!(parse_response(RIGHT), Verdict::Correct);
+        assert_eq!(parse_response(TOO_HIGH), Verdict::TooHigh);
+        assert_eq!(
+            parse_response(&TOO_HIGH.replace("high", "low")),
+            Verdict::TooLow
+        );
+        assert_eq!(parse_response(TOO_SOON), Verdict::Wait(65));
+        assert_eq!(
+            parse_response(
+                "<article><p>You don't seem to be solving the right level.  Did you already \
+                 complete it?</p></article>"
+            ),
+            Verdict::AlreadySolved
+        );
+        assert_eq!(
+            parse_response("<article><p>Something <em>new</em></p></article>"),
+            Verdict::Unknown("Something new".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_waits() {
+        assert_eq!(wait_seconds("You have 39s left to wait."), Some(39));
+        assert_eq!(wait_seconds("please wait 5 minutes before"), Some(300));
+        assert_eq!(wait_seconds("Please wait one minute before"), Some(60));
+        assert_eq!(wait_seconds("no waiting"), None);
+    }
+
+    #[test]
+    fn remembers_bounds() {
+        let mut guesses = Guesses::default();
+        guesses.record_wrong(2018, 1, Part::One, "500", &Verdict::TooHigh);
+        guesses.record_wrong(2018, 1, Part::One, "700", &Verdict::TooHigh);
+        guesses.record_wrong(2018, 1, Part::One, "100", &Verdict::TooLow);
+        guesses.record_wrong(2018, 1, Part::One, "abc", &Verdict::Wrong);
+        let wrong = |answer: &str| guesses.known_wrong(2018, 1, Part::One, answer);
+        assert!(wrong("600").is_some());
+        assert!(wrong("500").is_some());
+        assert!(wrong("50").is_some());
+        assert!(wrong("abc").is_some());
+        assert_eq!(wrong("499"), None);
+        assert_eq!(wrong("101"), None);
+        assert_eq!(guesses.known_wrong(2018, 1, Part::Two, "600"), None);
+    }
+
+    fn setup(name: &str, base_url: String) -> (Config, Files) {
+        let dir = stub::scratch(name);
+        let config = Config {
+            session: Some("abc123".to_string()),
+            base_url,
+            leaderboard: None,
+        };
+        let files = Files {
+            answers: dir.join("answers.toml"),
+            guesses: dir.join("guesses.toml"),
+        };
+        (config, files)
+    }
+
+    #[test]
+    fn records_right_answers() {
+        let (base_url, server) = stub::server(1, "200 OK", RIGHT);
+        let (config, files) = setup("submit-right", base_url);
+        let part = (2018, 1, Part::One);
+        assert_eq!(submit(&config, &files, part, "42", 0), Ok(Verdict::Correct));
+        let answers = Answers::load(&files.answers).unwrap();
+        assert_eq!(answers.get(2018, 1, Part::One), Some(Answer::Int(42)));
+        // and doesn't ask again
+        assert!(submit(&config, &files, part, "42", 0)
+            .unwrap_err()
+            .contains("already recorded"));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        assert!(
+            request.starts_with("POST /2018/day/1/answer HTTP/1.1"),
+            "{}",
+            request
+        );
+        assert!(request.to_lowercase().contains("cookie: session=abc123"));
+        assert!(
+            request.ends_with("\r\n\r\nlevel=1&answer=42"),
+            "{}",
+            request
+        );
+    }
+
+    #[test]
+    fn never_resubmits_wrong_answers() {
+        let (base_url, server) = stub::server(1, "200 OK", TOO_HIGH);
+        let (config, files) = setup("submit-wrong", base_url);
+        let part = (2018, 1, Part::Two);
+        assert_eq!(
+            submit(&config, &files, part, "900", 1000),
+            Ok(Verdict::TooHigh)
+        );
+        // still locked out for the minute a wrong answer costs
+        let error = submit(&config, &files, part, "800", 1030).unwrap_err();
+        assert!(error.contains("wait 30s"), "{}", error);
+        let error = submit(&config, &files, part, "901", 2000).unwrap_err();
+        assert!(error.contains("900 was too high"), "{}", error);
+        assert_eq!(server.join().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,51 @@
+//! Errors for malformed puzzle input.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Input that didn't look like what the puzzle promised.
+///
+/// `line` is 1-based, and 0 while the error hasn't been tied to a line yet. Line parsers usually
+/// don't know where they are in the file, so they leave it at 0 and [`crate::input::parse_lines`]
+/// fills it in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new<S: Into<String>>(text: &str, expected: S) -> ParseError {
+        ParseError {
+            line: 0,
+            text: text.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Tie the error to a line of the input, unless it already is.
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        if self.line == 0 {
+            self.line = line;
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}: ", self.line)?;
+        }
+        write!(f, "expected {}, found {:?}", self.expected, self.text)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse a single field, describing it as `expected` if it isn't valid.
+pub fn field<T: FromStr>(text: &str, expected: &str) -> Result<T, ParseError> {
+    text.parse().map_err(|_| ParseError::new(text, expected))
+}
@@ -0,0 +1,371 @@
+//! A dense 2D grid for the puzzles that draw on a plane.
+//!
+//! Cells are addressed by signed `(x, y)` points, x growing to the right and y growing down. A
+//! grid doesn't have to start at the origin: it covers whatever [`Bounds`] it was made with, so
+//! negative coordinates work without every caller shifting them by hand.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+pub type Point = (i64, i64);
+
+const NEIGHBORS4: [Point; 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const NEIGHBORS8: [Point; 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// An inclusive rectangle of points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl Bounds {
+    /// The smallest bounds holding every point, or `None` if there aren't any.
+    pub fn of<I: IntoIterator<Item = Point>>(points: I) -> Option<Bounds> {
+        let mut points = points.into_iter();
+        let (x, y) = points.next()?;
+        let start = Bounds {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        };
+        Some(points.fold(start, |b, (x, y)| Bounds {
+            min_x: b.min_x.min(x),
+            min_y: b.min_y.min(y),
+            max_x: b.max_x.max(x),
+            max_y: b.max_y.max(y),
+        }))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max_x - self.min_x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max_y - self.min_y + 1) as usize
+    }
+
+    /// Number of points inside, which can be far more than would fit in a grid.
+    pub fn area(&self) -> u64 {
+        self.width() as u64 * self.height() as u64
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        self.min_x <= x && x <= self.max_x && self.min_y <= y && y <= self.max_y
+    }
+
+    /// The bounds with `by` more points on every side.
+    pub fn grow(&self, by: i64) -> Bounds {
+        Bounds {
+            min_x: self.min_x - by,
+            min_y: self.min_y - by,
+            max_x: self.max_x + by,
+            max_y: self.max_y + by,
+        }
+    }
+
+    /// Every point inside, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let b = *self;
+        (b.min_y..=b.max_y).flat_map(move |y| (b.min_x..=b.max_x).map(move |x| (x, y)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    bounds: Bounds,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` by `height` grid with its top left corner at the origin.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        assert!(width > 0 && height > 0, "a grid needs at least one cell");
+        Grid::with_bounds(
+            Bounds {
+                min_x: 0,
+                min_y: 0,
+                max_x: width as i64 - 1,
+                max_y: height as i64 - 1,
+            },
+            fill,
+        )
+    }
+
+    /// A grid covering exactly `bounds`.
+    pub fn with_bounds(bounds: Bounds, fill: T) -> Grid<T> {
+        let cells = bounds
+            .width()
+            .checked_mul(bounds.height())
+            .expect("grid is too big to allocate");
+        Grid {
+            bounds,
+            cells: vec![fill; cells],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.bounds.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds.height()
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.bounds.contains(point)
+    }
+
+    fn offset(&self, (x, y): Point) -> Option<usize> {
+        if self.contains((x, y)) {
+            Some(self.offset_unchecked((x, y)))
+        } else {
+            None
+        }
+    }
+
+    fn offset_unchecked(&self, (x, y): Point) -> usize {
+        (y - self.bounds.min_y) as usize * self.width() + (x - self.bounds.min_x) as usize
+    }
+
+    fn point(&self, offset: usize) -> Point {
+        let width = self.width();
+        (
+            self.bounds.min_x + (offset % width) as i64,
+            self.bounds.min_y + (offset / width) as i64,
+        )
+    }
+
+    /// The cell at `point`, or `None` if it's off the grid.
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.offset(point).map(|o| &self.cells[o])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.offset(point).map(move |o| &mut self.cells[o])
+    }
+
+    /// The cell at `point` without checking each coordinate against the bounds.
+    ///
+    /// Only the final offset into the backing storage is checked, so a point off one edge can
+    /// land on a cell of a neighbouring row instead of panicking. For hot loops that already
+    /// know their points are inside; everything else should use [`Grid::get`] or indexing.
+    pub fn get_raw(&self, point: Point) -> &T {
+        &self.cells[self.offset_unchecked(point)]
+    }
+
+    pub fn get_raw_mut(&mut self, point: Point) -> &mut T {
+        let offset = self.offset_unchecked(point);
+        &mut self.cells[offset]
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        self.bounds.points()
+    }
+
+    /// Every cell with its point, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(o, v)| (self.point(o), v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut T)> {
+        let b = self.bounds;
+        let width = b.width();
+        self.cells.iter_mut().enumerate().map(move |(o, v)| {
+            let point = (b.min_x + (o % width) as i64, b.min_y + (o / width) as i64);
+            (point, v)
+        })
+    }
+
+    /// Every cell, row by row.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// The up to four points directly above, left, right and below `point` that are on the grid.
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbors(point, &NEIGHBORS4)
+    }
+
+    /// The up to eight points surrounding `point`, diagonals included, that are on the grid.
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbors(point, &NEIGHBORS8)
+    }
+
+    fn neighbors(
+        &self,
+        (x, y): Point,
+        deltas: &'static [Point],
+    ) -> impl Iterator<Item = Point> + '_ {
+        deltas
+            .iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(move |p| self.contains(*p))
+    }
+
+    /// The cells of row `y`, left to right.
+    pub fn row(&self, y: i64) -> impl Iterator<Item = &T> {
+        let b = self.bounds;
+        (b.min_x..=b.max_x).map(move |x| &self[(x, y)])
+    }
+
+    /// The cells of column `x`, top to bottom.
+    pub fn column(&self, x: i64) -> impl Iterator<Item = &T> {
+        let b = self.bounds;
+        (b.min_y..=b.max_y).map(move |y| &self[(x, y)])
+    }
+
+    /// Every cell on the outside edge of the grid, each one once.
+    pub fn edges(&self) -> impl Iterator<Item = (Point, &T)> {
+        let b = self.bounds;
+        self.iter().filter(move |((x, y), _)| {
+            *x == b.min_x || *x == b.max_x || *y == b.min_y || *y == b.max_y
+        })
+    }
+
+    /// Draw the grid one character per cell, with rows separated by newlines.
+    pub fn render<F: Fn(&T) -> char>(&self, f: F) -> String {
+        let mut buffer = String::with_capacity(self.cells.len() + self.height());
+        for (idx, row) in self.cells.chunks(self.width()).enumerate() {
+            if idx > 0 {
+                buffer.push('\n');
+            }
+            buffer.extend(row.iter().map(&f));
+        }
+        buffer
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.offset(point) {
+            Some(o) => &self.cells[o],
+            None => panic!("{:?} is outside of {:?}", point, self.bounds),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        match self.offset(point) {
+            Some(o) => &mut self.cells[o],
+            None => panic!("{:?} is outside of {:?}", point, self.bounds),
+        }
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(|c| *c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered() -> Grid<usize> {
+        let mut grid = Grid::with_bounds(
+            Bounds {
+                min_x: -1,
+                min_y: -2,
+                max_x: 1,
+                max_y: 0,
+            },
+            0,
+        );
+        for (n, (_, cell)) in grid.iter_mut().enumerate() {
+            *cell = n;
+        }
+        grid
+    }
+
+    #[test]
+    fn bounds_of_points() {
+        let b = Bounds::of(vec![(3, -2), (-1, 4), (0, 0)]).unwrap();
+        assert_eq!((b.min_x, b.min_y, b.max_x, b.max_y), (-1, -2, 3, 4));
+        assert_eq!((b.width(), b.height(), b.area()), (5, 7, 35));
+        assert_eq!(Bounds::of(Vec::new()), None);
+        let grown = b.grow(2);
+        assert_eq!((grown.min_x, grown.max_y), (-3, 6));
+        assert_eq!(grown.points().count() as u64, grown.area());
+    }
+
+    #[test]
+    fn signed_access() {
+        let mut grid = numbered();
+        assert_eq!(grid[(-1, -2)], 0);
+        assert_eq!(grid[(1, 0)], 8);
+        assert_eq!(grid.get((0, -1)), Some(&4));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 1)), None);
+        *grid.get_raw_mut((0, 0)) += 10;
+        assert_eq!(*grid.get_raw((0, 0)), 17);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let grid = numbered();
+        let _ = grid[(2, 0)];
+    }
+
+    #[test]
+    #[should_panic(expected = "grid is too big to allocate")]
+    fn too_big_to_allocate() {
+        Grid::new(usize::MAX / 2, 3, 0u8);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = numbered();
+        let corner: Vec<Point> = grid.neighbors4((-1, -2)).collect();
+        assert_eq!(corner, vec![(0, -2), (-1, -1)]);
+        assert_eq!(grid.neighbors4((0, -1)).count(), 4);
+        assert_eq!(grid.neighbors8((0, -1)).count(), 8);
+        assert_eq!(grid.neighbors8((1, 0)).count(), 3);
+    }
+
+    #[test]
+    fn rows_columns_and_edges() {
+        let grid = numbered();
+        assert_eq!(grid.row(-1).cloned().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(grid.column(1).cloned().collect::<Vec<_>>(), vec![2, 5, 8]);
+        let mut edges: Vec<usize> = grid.edges().map(|(_, v)| *v).collect();
+        edges.sort_unstable();
+        assert_eq!(edges, vec![0, 1, 2, 3, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn renders_rows() {
+        let grid = numbered();
+        assert_eq!(
+            grid.render(|n| if n % 2 == 0 { '#' } else { '.' }),
+            "#.#\n.#.\n#.#"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Reading puzzle input.
+
+use crate::error::ParseError;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Directory the personal puzzle inputs live in, as `inputs/<year>/<day>.txt`.
+pub const INPUTS_DIR: &str = "inputs";
+
+/// Where to read a day's input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// `-` means stdin, anything else is a path.
+    pub fn from_arg(arg: &str) -> Source {
+        if arg == "-" {
+            Source::Stdin
+        } else {
+            Source::File(PathBuf::from(arg))
+        }
+    }
+
+    /// The conventional location of a day's input, `inputs/<year>/<day>.txt`.
+    pub fn default_for(year: u16, day: u8) -> Source {
+        Source::File(default_path(year, day))
+    }
+
+    /// Use `arg` if one was given, otherwise the conventional location for the day.
+    pub fn resolve(arg: Option<&str>, year: u16, day: u8) -> Source {
+        match arg {
+            Some(arg) => Source::from_arg(arg),
+            None => Source::default_for(year, day),
+        }
+    }
+
+    /// Read and [`normalize`] the input.
+    pub fn read(&self) -> io::Result<String> {
+        let raw = match self {
+            Source::Stdin => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                buffer
+            }
+            Source::File(path) => read_to_string(path)?,
+        };
+        Ok(normalize(&raw))
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "<stdin>"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// `inputs/<year>/<day>.txt`, with the day zero padded like the crate directories.
+pub fn default_path(year: u16, day: u8) -> PathBuf {
+    Path::new(INPUTS_DIR)
+        .join(year.to_string())
+        .join(format!("{:02}.txt", day))
+}
+
+/// Read the whole input file into a string.
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut buffer = String::new();
+    file.read_to_string(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Clean up the ways a saved input tends to differ from the one on the website: a leading byte
+/// order mark, CRLF line endings and blank lines at the end.
+///
+/// ```
+/// use advent_common::input::normalize;
+/// assert_eq!(normalize("\u{feff}+1\r\n-2\r\n\r\n"), "+1\n-2");
+/// ```
+pub fn normalize(raw: &str) -> String {
+    let raw = raw.trim_start_matches('\u{feff}');
+    let mut lines: Vec<&str> = raw.lines().map(|l| l.trim_end_matches('\r')).collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Split already loaded input into lines, dropping any trailing `\r`.
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    input.lines().map(|l| l.trim_end_matches('\r'))
+}
+
+/// The first line of the input, for the days whose input is a single line.
+pub fn first_line(input: &str) -> &str {
+    lines(input).next().unwrap_or("")
+}
+
+/// Parse every line with `parse`, stopping at the first bad one and recording its line number.
+pub fn parse_lines<T, F>(input: &str, parse: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    lines(input)
+        .enumerate()
+        .map(|(idx, l)| parse(l).map_err(|e| e.at_line(idx + 1)))
+        .collect()
+}
@@ -0,0 +1,33 @@
+//! Shared scaffolding for the advent of code solutions.
+//!
+//! Every day used to carry its own copy of the input loading and line parsing, this crate is
+//! where that lives now.
+
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod pattern;
+pub mod rng;
+pub mod solution;
+
+/// Implement `FromStr` for a struct from a pattern like `#{id} @ {left},{top}: {width}x{height}`.
+///
+/// ```
+/// use advent_common::FromLine;
+///
+/// #[derive(Debug, FromLine)]
+/// #[line("{players} players; last marble is worth {points} points")]
+/// struct Game {
+///     players: u16,
+///     points: u64,
+/// }
+///
+/// let game: Game = "10 players; last marble is worth 1618 points".parse().unwrap();
+/// assert_eq!((game.players, game.points), (10, 1618));
+/// assert!("10 players".parse::<Game>().is_err());
+/// ```
+pub use advent_derive::FromLine;
+pub use error::ParseError;
+pub use grid::{Bounds, Grid, Point};
+pub use rng::Rng;
+pub use solution::{load, run, Answer, Part, Puzzle, Solution};
@@ -0,0 +1,85 @@
+//! Matching lines against the patterns of `#[derive(FromLine)]`.
+//!
+//! A pattern is literal text with `{field}` placeholders, like `#{id} @ {left},{top}: {width}x{height}`.
+//! The derive splits it into the literals around the placeholders at compile time and the
+//! generated `FromStr` hands them to [`captures`] along with the line.
+
+/// The text of each placeholder, given the literals before, between and after them.
+///
+/// The line has to start with the first literal and end with the last one. Each placeholder runs
+/// up to the next occurrence of the literal that follows it, and is trimmed so padding like the
+/// `< 5, -2>` of day 10 doesn't need spelling out. Returns `None` if the line doesn't fit.
+///
+/// ```
+/// use advent_common::pattern::captures;
+/// let literals = ["#", " @ ", ",", ": ", "x", ""];
+/// assert_eq!(
+///     captures("#1 @ 1,3: 4x4", &literals),
+///     Some(vec!["1", "1", "3", "4", "4"])
+/// );
+/// assert_eq!(captures("#1 @ 1,3 4x4", &literals), None);
+/// ```
+pub fn captures<'a>(line: &'a str, literals: &[&str]) -> Option<Vec<&'a str>> {
+    let (first, rest) = literals.split_first()?;
+    let mut remaining = line.strip_prefix(first)?;
+    let mut fields = Vec::with_capacity(rest.len());
+    for (idx, literal) in rest.iter().enumerate() {
+        if idx + 1 == rest.len() {
+            // the last placeholder takes everything up to the closing literal
+            fields.push(remaining.strip_suffix(literal)?.trim());
+            remaining = "";
+        } else {
+            let end = remaining.find(literal)?;
+            fields.push(remaining[..end].trim());
+            remaining = &remaining[end + literal.len()..];
+        }
+    }
+    if remaining.is_empty() {
+        Some(fields)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_line_has_to_match() {
+        let literals = ["Step ", " must be finished before step ", " can begin."];
+        assert_eq!(
+            captures(
+                "Step C must be finished before step A can begin.",
+                &literals
+            ),
+            Some(vec!["C", "A"])
+        );
+        assert_eq!(
+            captures("Step C must be finished before step A can begin", &literals),
+            None
+        );
+        assert_eq!(
+            captures(
+                "Oh Step C must be finished before step A can begin.",
+                &literals
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn placeholders_are_trimmed() {
+        let literals = ["position=<", ",", "> velocity=<", ",", ">"];
+        assert_eq!(
+            captures("position=< 5, -2> velocity=< 1,  2>", &literals),
+            Some(vec!["5", "-2", "1", "2"])
+        );
+    }
+
+    #[test]
+    fn no_placeholders() {
+        assert_eq!(captures("wakes up", &["wakes up"]), Some(vec![]));
+        assert_eq!(captures("wakes up!", &["wakes up"]), None);
+    }
+}
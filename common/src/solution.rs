@@ -0,0 +1,162 @@
+//! The interface every day implements so the runner can drive it.
+
+use crate::error::ParseError;
+use crate::input;
+use crate::rng::Rng;
+use std::any::Any;
+use std::convert::TryFrom;
+use std::fmt;
+use std::process;
+
+/// Which half of a day's puzzle to solve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// A puzzle answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    /// A multiline picture that has to be read by a human, like day 10 of 2018.
+    Art(String),
+    /// The input has no answer, and why. It's never sent to the site.
+    Unsolved(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Text(s) | Answer::Art(s) => write!(f, "{}", s),
+            Answer::Unsolved(why) => write!(f, "no answer ({})", why),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Answer {
+                    Answer::Int(i64::try_from(n).expect("answer doesn't fit in an i64"))
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, u32, i64, u64, usize);
+
+impl From<i128> for Answer {
+    /// Too big for an [`Answer::Int`], it's still the answer, just written out as text.
+    fn from(n: i128) -> Answer {
+        i64::try_from(n).map_or_else(|_| Answer::Text(n.to_string()), Answer::Int)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// A single day's solution: parse the input once, then solve each part from the parsed form.
+pub trait Solution {
+    const YEAR: u16;
+    const DAY: u8;
+    type Input: 'static;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+
+    /// Random input that follows the puzzle's rules, about `size` items large (lines, units or
+    /// nodes, whatever the day counts in). Days without a generator return `None`.
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+}
+
+/// Object safe view of a [`Solution`], so the runner can keep every day in one list.
+///
+/// There's a blanket implementation for every `Solution`, days should never need to implement it
+/// themselves.
+pub trait Puzzle: Sync {
+    fn year(&self) -> u16;
+    fn day(&self) -> u8;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+    fn solve(&self, input: &dyn Any, part: Part) -> Answer;
+    fn generate(&self, seed: u64, size: usize) -> Option<String>;
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn year(&self) -> u16 {
+        S::YEAR
+    }
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        S::parse(input).map(|parsed| Box::new(parsed) as Box<dyn Any>)
+    }
+    fn solve(&self, input: &dyn Any, part: Part) -> Answer {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input was parsed by a different day");
+        match part {
+            Part::One => S::part1(input),
+            Part::Two => S::part2(input),
+        }
+    }
+    fn generate(&self, seed: u64, size: usize) -> Option<String> {
+        S::generate(&mut Rng::new(seed), size)
+    }
+}
+
+/// Read and parse the input named by the first command line argument (a path, or `-` for stdin),
+/// falling back to `inputs/<year>/<day>.txt`. Exits with a diagnostic if that fails.
+pub fn load<S: Solution>() -> S::Input {
+    let arg = std::env::args().nth(1);
+    let source = input::Source::resolve(arg.as_deref(), S::YEAR, S::DAY);
+    let raw = source.read().unwrap_or_else(|e| {
+        eprintln!("Couldn't read {}: {}", source, e);
+        process::exit(1);
+    });
+    S::parse(&raw).unwrap_or_else(|e| {
+        eprintln!("Couldn't parse {}: {}", source, e);
+        process::exit(1);
+    })
+}
+
+/// Entry point for each day's own binary: solve both parts and print the answers.
+pub fn run<S: Solution>() {
+    let parsed = load::<S>();
+    println!("{}", S::part1(&parsed));
+    println!("{}", S::part2(&parsed));
+}
@@ -0,0 +1,143 @@
+//! `#[derive(FromLine)]`, re-exported by `advent-common`.
+//!
+//! Deriving `FromLine` on a struct with named fields and a `#[line("...")]` pattern implements
+//! `FromStr` with `advent_common::ParseError` as the error:
+//!
+//! ```ignore
+//! #[derive(FromLine)]
+//! #[line("#{id} @ {left},{top}: {width}x{height}")]
+//! pub struct Claim {
+//!     pub id: u32,
+//!     pub left: usize,
+//!     pub top: usize,
+//!     pub width: usize,
+//!     pub height: usize,
+//! }
+//! ```
+//!
+//! Every field has to appear in the pattern exactly once, and is parsed with its own `FromStr`.
+//! Placeholders need some literal text between them so the line can be split unambiguously;
+//! mistakes in the pattern are reported when the crate is compiled rather than on the first line.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr};
+
+#[proc_macro_derive(FromLine, attributes(line))]
+pub fn derive_from_line(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Split a pattern into the literals around its placeholders and the placeholder names.
+fn split_pattern(pattern: &str) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut literals = vec![String::new()];
+    let mut names = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                if name.is_empty() {
+                    return Err("empty or unclosed placeholder".to_string());
+                }
+                if literals.last().is_some_and(|l| l.is_empty()) && !names.is_empty() {
+                    return Err(format!(
+                        "`{{{}}}` needs some literal text between it and the placeholder before it",
+                        name
+                    ));
+                }
+                names.push(name);
+                literals.push(String::new());
+            }
+            '}' => return Err("unmatched `}`".to_string()),
+            c => literals.last_mut().expect("always one literal").push(c),
+        }
+    }
+    Ok((literals, names))
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "FromLine needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "FromLine can only be derived for structs",
+            ))
+        }
+    };
+    let attr = input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("line"))
+        .ok_or_else(|| syn::Error::new_spanned(ident, "missing #[line(\"...\")] pattern"))?;
+    let pattern: LitStr = attr.parse_args()?;
+    let (literals, names) =
+        split_pattern(&pattern.value()).map_err(|e| syn::Error::new_spanned(&pattern, e))?;
+
+    for field in fields {
+        let name = field.ident.as_ref().expect("named field").to_string();
+        match names.iter().filter(|n| **n == name).count() {
+            1 => {}
+            0 => {
+                return Err(syn::Error::new_spanned(
+                    &pattern,
+                    format!("`{}` is missing from the pattern", name),
+                ))
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &pattern,
+                    format!("`{}` appears more than once in the pattern", name),
+                ))
+            }
+        }
+    }
+
+    let template = pattern.value();
+    let mut parsed = Vec::new();
+    for (idx, name) in names.iter().enumerate() {
+        let field = fields
+            .iter()
+            .find(|f| f.ident.as_ref().is_some_and(|i| i == name))
+            .ok_or_else(|| {
+                syn::Error::new_spanned(&pattern, format!("no field called `{}`", name))
+            })?;
+        let field_ident = Ident::new(name, pattern.span());
+        let ty = &field.ty;
+        let expected = format!("`{}` in `{}`", name, template);
+        parsed.push(quote! {
+            #field_ident: ::advent_common::error::field::<#ty>(fields[#idx], #expected)?
+        });
+    }
+    let expected = format!("a line like `{}`", template);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #ident #ty_generics #where_clause {
+            type Err = ::advent_common::ParseError;
+
+            fn from_str(line: &str) -> ::std::result::Result<Self, ::advent_common::ParseError> {
+                let fields = ::advent_common::pattern::captures(line, &[#(#literals),*])
+                    .ok_or_else(|| ::advent_common::ParseError::new(line, #expected))?;
+                ::std::result::Result::Ok(#ident {
+                    #(#parsed,)*
+                })
+            }
+        }
+    })
+}
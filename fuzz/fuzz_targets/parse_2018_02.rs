@@ -0,0 +1,8 @@
+#![no_main]
+
+use advent_common::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = advent_2018_02::Day02::parse(&String::from_utf8_lossy(data));
+});